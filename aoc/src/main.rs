@@ -0,0 +1,369 @@
+use aoclib::answers::{self, Check};
+use aoclib::bench::{self, CountingAlloc};
+use aoclib::fileops;
+use aoclib::solution::{Answer, DynSolution, Part};
+use std::any::Any;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{Duration, Instant};
+
+const USAGE: &str = "usage: aoc [DAY] [--part 1|2] [--input PATH | --verify | --bless]
+       aoc [DAY] --bench [--input PATH] [--iterations N] [--json PATH]
+runs every day if no DAY is given, the input defaults to dayN/input.txt
+--verify checks input.txt and test_input.txt against dayN/answers.toml
+--bless records their answers in dayN/answers.toml
+--bench times parse, part1 and part2 on their own, N times each";
+
+const DEFAULT_ITERATIONS: usize = 20;
+
+/// Counts allocations for the benchmarks
+#[global_allocator]
+static ALLOCATOR: CountingAlloc = CountingAlloc;
+
+/// Every day the runner can run, a new day only has to be added here
+const SOLUTIONS: &[&dyn DynSolution] = &[
+    &day1::Day1,
+    &day2::Day2,
+    &day3::Day3,
+    &day4::Day4,
+    &day5::Day5,
+    &day6::Day6,
+    &day7::Day7,
+    &day8::Day8,
+    &day9::Day9,
+    &day10::Day10,
+    &day11::Day11,
+];
+
+#[derive(Debug, Default, PartialEq, Eq)]
+enum Mode
+{
+    #[default]
+    Run,
+    Verify,
+    Bless,
+    Bench,
+    Help,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Args
+{
+    day: Option<u32>,
+    part: Option<Part>,
+    input: Option<PathBuf>,
+    mode: Mode,
+    iterations: Option<usize>,
+    json: Option<PathBuf>,
+}
+
+fn main() {
+    let args = parse_args(env::args().skip(1)).unwrap_or_else(|error| {
+        eprintln!("{}\n{}", error, USAGE);
+        process::exit(2);
+    });
+    let result = match args.mode {
+        Mode::Run => run(&args),
+        Mode::Verify | Mode::Bless => verify(&args),
+        Mode::Bench => bench(&args),
+        Mode::Help => {
+            println!("{}", USAGE);
+            Ok(())
+        },
+    };
+    if let Err(error) = result {
+        eprintln!("{}", error);
+        process::exit(1);
+    }
+}
+
+fn parse_args<I>(args: I)
+    -> Result<Args, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut parsed = Args::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--part" => {
+                let part = args.next().ok_or("--part needs a value")?;
+                match part.as_str() {
+                    "1" => parsed.part = Some(Part::One),
+                    "2" => parsed.part = Some(Part::Two),
+                    _ => return Err(format!("part must be 1 or 2, not {}", part)),
+                }
+            },
+            "--input" => {
+                let input = args.next().ok_or("--input needs a value")?;
+                parsed.input = Some(PathBuf::from(input));
+            },
+            "--verify" => parsed.mode = Mode::Verify,
+            "--bless" => parsed.mode = Mode::Bless,
+            "--bench" => parsed.mode = Mode::Bench,
+            "--iterations" => {
+                let iterations = args.next().ok_or("--iterations needs a value")?;
+                match iterations.parse() {
+                    Ok(n) if n > 0 => parsed.iterations = Some(n),
+                    _ => return Err(format!("not a number of iterations: {}", iterations)),
+                }
+            },
+            "--json" => {
+                let json = args.next().ok_or("--json needs a value")?;
+                parsed.json = Some(PathBuf::from(json));
+            },
+            "-h" | "--help" => return Ok(Args { mode: Mode::Help, ..Args::default() }),
+            day if parsed.day.is_none() && !day.starts_with('-') => {
+                let day = day.parse().map_err(|_| { format!("not a day: {}", day) })?;
+                parsed.day = Some(day);
+            },
+            _ => return Err(format!("unexpected argument {}", arg)),
+        }
+    }
+    if parsed.input.is_some() && parsed.day.is_none() {
+        return Err("--input needs a DAY".to_string());
+    }
+    if parsed.input.is_some() && matches!(parsed.mode, Mode::Verify | Mode::Bless) {
+        return Err("--verify and --bless use the inputs of the day".to_string());
+    }
+    if (parsed.iterations.is_some() || parsed.json.is_some()) && parsed.mode != Mode::Bench {
+        return Err("--iterations and --json need --bench".to_string());
+    }
+    if parsed.part.is_some() && parsed.mode == Mode::Bench {
+        return Err("--bench runs both parts".to_string());
+    }
+    Ok(parsed)
+}
+
+fn find_solution(day: u32)
+    -> Result<&'static dyn DynSolution, String>
+{
+    SOLUTIONS.iter()
+        .find(|x| { x.day() == day })
+        .copied()
+        .ok_or(format!("no solution registered for day {}", day))
+}
+
+/// The day's crate, found from the workspace and not the working
+/// directory so the runner can be started from anywhere
+fn day_dir(day: u32)
+    -> PathBuf
+{
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join(format!("day{}", day))
+}
+
+fn default_input(day: u32)
+    -> PathBuf
+{
+    day_dir(day).join("input.txt")
+}
+
+/// Solutions and parts picked by the arguments
+fn selection(args: &Args)
+    -> Result<(Vec<&'static dyn DynSolution>, Vec<Part>), String>
+{
+    let solutions = match args.day {
+        Some(day) => vec![find_solution(day)?],
+        None => SOLUTIONS.to_vec(),
+    };
+    let parts = match args.part {
+        Some(part) => vec![part],
+        None => Part::BOTH.to_vec(),
+    };
+    Ok((solutions, parts))
+}
+
+fn run(args: &Args)
+    -> Result<(), String>
+{
+    let (solutions, parts) = selection(args)?;
+
+    println!("{:>3}  {:>4}  {:<16}  {:>12}", "day", "part", "answer", "time");
+    let mut total = Duration::ZERO;
+    for solution in solutions {
+        let text = read_input(args, solution.day())?;
+        // parsed once, like the Solution trait promises, and timed on its own
+        let now = Instant::now();
+        let input = solution.parse_dyn(&text);
+        let elapsed = now.elapsed();
+        total += elapsed;
+        println!("{:>3}  {:>4}  {:<16}  {:>12}", solution.day(), "", "parse",
+            format!("{:.2?}", elapsed));
+        for &part in &parts {
+            let (answer, elapsed) = run_part(solution, part, input.as_ref());
+            total += elapsed;
+            println!("{:>3}  {:>4}  {:<16}  {:>12}", solution.day(), part, answer,
+                format!("{:.2?}", elapsed));
+        }
+    }
+    println!("{:>3}  {:>4}  {:<16}  {:>12}", "", "", "total", format!("{:.2?}", total));
+    Ok(())
+}
+
+fn read_input(args: &Args, day: u32)
+    -> Result<String, String>
+{
+    let input = match &args.input {
+        Some(input) => input.clone(),
+        None => default_input(day),
+    };
+    if !input.is_file() {
+        return Err(format!("day {}: no input at {}", day, input.display()));
+    }
+    Ok(fileops::read_file(&input))
+}
+
+/// Time every stage of the days, a row per stage
+fn bench(args: &Args)
+    -> Result<(), String>
+{
+    let (solutions, _) = selection(args)?;
+    let iterations = args.iterations.unwrap_or(DEFAULT_ITERATIONS);
+
+    println!("{:>3}  {:<5}  {:>12}  {:>12}  {:>12}  {:>8}  {:>10}",
+        "day", "stage", "min ns", "median ns", "p95 ns", "allocs", "bytes");
+    let show = |x: Option<u64>| { x.map_or("-".to_string(), |x| { x.to_string() }) };
+    let mut benches = Vec::new();
+    for solution in solutions {
+        let text = read_input(args, solution.day())?;
+        let day = bench::bench_day(solution, &text, iterations);
+        for (stage, stats) in day.stages() {
+            println!("{:>3}  {:<5}  {:>12}  {:>12}  {:>12}  {:>8}  {:>10}",
+                day.day, stage, stats.min, stats.median, stats.p95,
+                show(stats.allocations), show(stats.allocated_bytes));
+        }
+        benches.push(day);
+    }
+    if let Some(json) = &args.json {
+        fs::write(json, bench::to_json(&benches))
+            .map_err(|error| { format!("{}: {}", json.display(), error) })?;
+    }
+    Ok(())
+}
+
+/// Check every input of the days against their answers.toml,
+/// or record the answers when blessing
+fn verify(args: &Args)
+    -> Result<(), String>
+{
+    let (solutions, parts) = selection(args)?;
+    let mut wrong: Vec<(u32, Check)> = Vec::new();
+    for solution in solutions {
+        let day = solution.day();
+        let dir = day_dir(day);
+        let checks = answers::check_day(solution, &dir, &parts)
+            .map_err(|error| { format!("day {}: {}", day, error) })?;
+        if checks.is_empty() {
+            println!("day {}: no inputs", day);
+        }
+        if args.mode == Mode::Bless {
+            let changed = answers::bless(&dir, &checks)
+                .map_err(|error| { format!("day {}: {}", day, error) })?;
+            for check in changed {
+                println!("day {} {}.txt part{}: recorded {}", day, check.input, check.part,
+                    check.actual);
+            }
+            continue;
+        }
+        for check in checks {
+            println!("day {} {}", day, check);
+            if !check.is_ok() {
+                wrong.push((day, check));
+            }
+        }
+    }
+    if wrong.is_empty() {
+        return Ok(());
+    }
+    println!();
+    for (day, check) in &wrong {
+        println!("day {} {}\n{}", day, check, check.diff());
+    }
+    Err(format!("{} answers wrong or not recorded, record new answers with --bless",
+        wrong.len()))
+}
+
+/// Solve a part of the input parse_dyn returned, timing only the part
+fn run_part(solution: &dyn DynSolution, part: Part, input: &dyn Any)
+    -> (Answer, Duration)
+{
+    let now = Instant::now();
+    let answer = solution.solve_dyn(input, part);
+    (answer, now.elapsed())
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn args(args: &[&str])
+        -> Result<Args, String>
+    {
+        parse_args(args.iter().map(|x| { x.to_string() }))
+    }
+
+    #[test]
+    /// test parsing the command line
+    fn test_0x0001()
+    {
+        assert_eq!(args(&[]), Ok(Args::default()));
+        assert_eq!(args(&["9", "--part", "2", "--input", "path"]), Ok(Args {
+            day: Some(9),
+            part: Some(Part::Two),
+            input: Some(PathBuf::from("path")),
+            mode: Mode::Run,
+            ..Args::default()
+        }));
+        assert_eq!(args(&["--bless", "5"]).unwrap(), Args {
+            day: Some(5),
+            mode: Mode::Bless,
+            ..Args::default()
+        });
+        assert!(args(&["5", "--verify", "--input", "path"]).is_err());
+        assert_eq!(args(&["--bench", "--iterations", "3", "--json", "out.json"]).unwrap(), Args {
+            mode: Mode::Bench,
+            iterations: Some(3),
+            json: Some(PathBuf::from("out.json")),
+            ..Args::default()
+        });
+        assert!(args(&["--bench", "--iterations", "0"]).is_err());
+        assert!(args(&["--iterations", "3"]).is_err());
+        assert!(args(&["--bench", "--part", "1"]).is_err());
+        assert_eq!(args(&["--part", "1"]).unwrap().part, Some(Part::One));
+        assert!(args(&["--part", "3"]).is_err());
+        assert!(args(&["--input", "path"]).is_err());
+        assert!(args(&["9", "10"]).is_err());
+        assert!(args(&["nine"]).is_err());
+        assert_eq!(args(&["5", "--help"]).unwrap().mode, Mode::Help);
+    }
+
+    #[test]
+    /// test every day is registered once, in order, with its input
+    fn test_0x0002()
+    {
+        let days: Vec<u32> = SOLUTIONS.iter().map(|x| { x.day() }).collect();
+        assert_eq!(days, (1..=11).collect::<Vec<_>>());
+        for day in days {
+            assert!(default_input(day).is_file(), "day {} has no input", day);
+        }
+        assert!(find_solution(12).is_err());
+    }
+
+    #[test]
+    /// test the runner counts allocations of a benchmark
+    fn test_0x0003()
+    {
+        assert!(bench::is_counting());
+        let text = fileops::read_file(day_dir(1).join("test_input.txt"));
+        let day = bench::bench_day(find_solution(1).unwrap(), &text, 3);
+        assert_eq!(day.parse.iterations, 3);
+        // one vector of depths per parse, at least
+        assert!(day.parse.allocations.unwrap() >= 1);
+        assert!(day.parse.allocated_bytes.unwrap() >= 10 * 4);
+    }
+}
@@ -0,0 +1,118 @@
+//! Throughput of dispatching tiny jobs, lock free queue against the old
+//! design where every worker locks one shared mpsc receiver
+//!
+//! cargo bench -p aoclib --bench dispatch
+//!
+//! Measured on a 1 CPU host, 1/2/4 threads, million jobs per second:
+//!
+//!   mutex      13.6-15.3
+//!   lock free  12.0-14.1
+//!
+//! With one CPU there is no contention on the shared receiver for the lock
+//! free queue to win back, so what is left is the bookkeeping every dispatch
+//! pays for: the submitted/finished counters wait_idle needs to tell a busy
+//! pool from an idle one, and polling the priority queues. A run on a multi
+//! core host is still to be done.
+
+use aoclib::threadpool::ThreadPool;
+use std::sync::{
+    mpsc, Arc, Mutex,
+};
+use std::thread;
+use std::time;
+
+const NUM_JOBS: usize = 1_000_000;
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+enum Message
+{
+    NewJob(Job),
+    Terminate,
+}
+
+/// The dispatch path ThreadPool used before, kept here as the baseline
+struct MutexPool
+{
+    workers: Vec<thread::JoinHandle<()>>,
+    dispatcher: mpsc::Sender<Message>,
+}
+
+impl MutexPool
+{
+    fn new(size: usize)
+        -> MutexPool
+    {
+        let (dispatcher, reciever) = mpsc::channel();
+        let reciever = Arc::new(Mutex::new(reciever));
+        let workers = (0..size).map(|_| {
+            let reciever: Arc<Mutex<mpsc::Receiver<Message>>> = Arc::clone(&reciever);
+            thread::spawn(move || loop {
+                let message = reciever.lock().expect("poisoned reciever")
+                    .recv().expect("no dispatcher");
+                match message {
+                    Message::NewJob(job) => job(),
+                    Message::Terminate => break,
+                }
+            })
+        }).collect();
+        MutexPool { workers, dispatcher }
+    }
+
+    fn execute<F>(&self, f: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.dispatcher.send(Message::NewJob(Box::new(f))).unwrap();
+    }
+}
+
+impl Drop for MutexPool
+{
+    fn drop(&mut self) {
+        for _ in &self.workers {
+            self.dispatcher.send(Message::Terminate).unwrap();
+        }
+        for worker in self.workers.drain(..) {
+            worker.join().unwrap();
+        }
+    }
+}
+
+/// Time from the first job submitted until every job has run
+fn time_jobs<P, E>(pool: P, execute: E)
+    -> time::Duration
+where
+    E: Fn(&P),
+{
+    let now = time::Instant::now();
+    for _ in 0..NUM_JOBS {
+        execute(&pool);
+    }
+    drop(pool);
+    now.elapsed()
+}
+
+fn report(name: &str, threads: usize, elapsed: time::Duration)
+{
+    println!("{:<10} {:>2} threads {:>8.1} ms {:>12.0} jobs/s",
+        name,
+        threads,
+        elapsed.as_secs_f64() * 1000.0,
+        NUM_JOBS as f64 / elapsed.as_secs_f64());
+}
+
+fn main() {
+    let available = thread::available_parallelism().map_or(1, |x| { x.get() });
+    let mut thread_counts = vec![1, 2, 4, available];
+    thread_counts.sort_unstable();
+    thread_counts.dedup();
+
+    println!("{} no-op jobs", NUM_JOBS);
+    for threads in thread_counts {
+        let elapsed = time_jobs(MutexPool::new(threads), |pool| { pool.execute(| | {}) });
+        report("mutex", threads, elapsed);
+        let elapsed = time_jobs(ThreadPool::new(threads), |pool| { pool.execute(| | {}) });
+        report("lock free", threads, elapsed);
+    }
+}
@@ -0,0 +1,376 @@
+//! Expected answers of a day, kept in answers.toml next to its inputs,
+//! with a table per input file named by the file without .txt
+//!
+//! ```toml
+//! [input]
+//! part1 = 1722
+//! part2 = 1748
+//!
+//! [test_input]
+//! part1 = 7
+//! part2 = 5
+//! ```
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::fileops;
+use super::solution::{
+    Answer, DynSolution, Part,
+};
+
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+/// Input files of a day that are checked, the ones that exist
+pub const INPUTS: [&str; 2] = ["input", "test_input"];
+
+#[derive(Debug)]
+pub enum AnswersError
+{
+    Io(io::Error),
+    Parse(toml::de::Error),
+    /// Not a table of part1 and part2, or an answer that isn't
+    /// an integer or a string
+    Invalid { input: String, key: String },
+}
+
+impl fmt::Display for AnswersError
+{
+    fn fmt(&self, f: &mut fmt::Formatter)
+        -> fmt::Result
+    {
+        match self {
+            AnswersError::Io(error) => write!(f, "{}", error),
+            AnswersError::Parse(error) => write!(f, "{}", error),
+            AnswersError::Invalid { input, key } =>
+                write!(f, "invalid answer {} of {}", key, input),
+        }
+    }
+}
+
+impl Error for AnswersError {}
+
+impl From<io::Error> for AnswersError
+{
+    fn from(error: io::Error)
+        -> AnswersError
+    {
+        AnswersError::Io(error)
+    }
+}
+
+impl From<toml::de::Error> for AnswersError
+{
+    fn from(error: toml::de::Error)
+        -> AnswersError
+    {
+        AnswersError::Parse(error)
+    }
+}
+
+fn part_key(part: Part)
+    -> String
+{
+    format!("part{}", part)
+}
+
+/// Answers are compared as text, so 5 and "5" are the same answer
+fn same_answer(a: &Answer, b: &Answer)
+    -> bool
+{
+    a.to_string() == b.to_string()
+}
+
+/// Expected answer of each part by input file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers
+{
+    expected: BTreeMap<String, BTreeMap<Part, Answer>>,
+}
+
+impl Answers
+{
+    pub fn new()
+        -> Answers
+    {
+        Answers::default()
+    }
+
+    pub fn parse(text: &str)
+        -> Result<Answers, AnswersError>
+    {
+        let mut answers = Answers::new();
+        for (input, parts) in text.parse::<toml::Table>()? {
+            let invalid = |key: &str| {
+                AnswersError::Invalid { input: input.clone(), key: key.to_string() }
+            };
+            let parts = parts.as_table().ok_or_else(|| { invalid("") })?;
+            for (key, value) in parts {
+                let part = Part::BOTH.into_iter()
+                    .find(|&x| { part_key(x) == *key })
+                    .ok_or_else(|| { invalid(key) })?;
+                let answer = match value {
+                    toml::Value::Integer(x) => Answer::from(*x),
+                    toml::Value::String(x) => Answer::from(x.as_str()),
+                    _ => return Err(invalid(key)),
+                };
+                answers.set(&input, part, answer);
+            }
+        }
+        Ok(answers)
+    }
+
+    /// No file means nothing is recorded yet
+    pub fn load<P>(path: P)
+        -> Result<Answers, AnswersError>
+    where
+        P: AsRef<Path>,
+    {
+        match fs::read_to_string(path) {
+            Ok(text) => Answers::parse(&text),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Answers::new()),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    pub fn save<P>(&self, path: P)
+        -> Result<(), AnswersError>
+    where
+        P: AsRef<Path>,
+    {
+        fs::write(path, self.to_string())?;
+        Ok(())
+    }
+
+    pub fn is_empty(&self)
+        -> bool
+    {
+        self.expected.is_empty()
+    }
+
+    pub fn get(&self, input: &str, part: Part)
+        -> Option<&Answer>
+    {
+        self.expected.get(input)?.get(&part)
+    }
+
+    /// Keeps the old answer if it's the same, returns true if it
+    /// was new or changed
+    pub fn set(&mut self, input: &str, part: Part, answer: Answer)
+        -> bool
+    {
+        let parts = self.expected.entry(input.to_string()).or_default();
+        let changed = parts.get(&part)
+            .is_none_or(|x| { !same_answer(x, &answer) });
+        if changed {
+            parts.insert(part, answer);
+        }
+        changed
+    }
+}
+
+impl fmt::Display for Answers
+{
+    fn fmt(&self, f: &mut fmt::Formatter)
+        -> fmt::Result
+    {
+        let mut tables = Vec::new();
+        for (input, parts) in &self.expected {
+            let mut table = format!("[{}]\n", input);
+            for (&part, answer) in parts {
+                // toml integers are i64, bigger numbers are kept as text
+                let value = match answer {
+                    Answer::Number(x) if i64::try_from(*x).is_ok() => x.to_string(),
+                    answer => toml::Value::String(answer.to_string()).to_string(),
+                };
+                table += &format!("{} = {}\n", part_key(part), value);
+            }
+            tables.push(table);
+        }
+        write!(f, "{}", tables.join("\n"))
+    }
+}
+
+/// Answer of one part for one input, and what answers.toml expects
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check
+{
+    pub input: String,
+    pub part: Part,
+    pub expected: Option<Answer>,
+    pub actual: Answer,
+}
+
+impl Check
+{
+    /// False if the answer is wrong or not recorded
+    pub fn is_ok(&self)
+        -> bool
+    {
+        self.expected.as_ref().is_some_and(|x| { same_answer(x, &self.actual) })
+    }
+
+    /// Expected and actual answer line by line, lines that differ as
+    /// "- expected" and "+ actual", the rest indented
+    pub fn diff(&self)
+        -> String
+    {
+        let expected = match &self.expected {
+            Some(expected) => expected.to_string(),
+            None => return format!("+ {}\n", self.actual),
+        };
+        let actual = self.actual.to_string();
+        let expected: Vec<&str> = expected.lines().collect();
+        let actual: Vec<&str> = actual.lines().collect();
+        let mut diff = String::new();
+        for i in 0..expected.len().max(actual.len()) {
+            match (expected.get(i), actual.get(i)) {
+                (Some(e), Some(a)) if e == a => diff += &format!("  {}\n", e),
+                (e, a) => {
+                    if let Some(e) = e {
+                        diff += &format!("- {}\n", e);
+                    }
+                    if let Some(a) = a {
+                        diff += &format!("+ {}\n", a);
+                    }
+                },
+            }
+        }
+        diff
+    }
+}
+
+impl fmt::Display for Check
+{
+    fn fmt(&self, f: &mut fmt::Formatter)
+        -> fmt::Result
+    {
+        let status = match &self.expected {
+            _ if self.is_ok() => "ok",
+            Some(_) => "wrong",
+            None => "not recorded",
+        };
+        write!(f, "{}.txt part{}: {}", self.input, self.part, status)
+    }
+}
+
+/// Solve the parts for every input of the day in dir and compare
+/// with the answers.toml there
+pub fn check_day<P>(solution: &dyn DynSolution, dir: P, parts: &[Part])
+    -> Result<Vec<Check>, AnswersError>
+where
+    P: AsRef<Path>,
+{
+    let dir = dir.as_ref();
+    let answers = Answers::load(dir.join(ANSWERS_FILE))?;
+    let mut checks = Vec::new();
+    for input in INPUTS {
+        let path = dir.join(format!("{}.txt", input));
+        if !path.is_file() {
+            continue;
+        }
+        let parsed = solution.parse_dyn(&fileops::read_file(path));
+        for &part in parts {
+            checks.push(Check {
+                input: input.to_string(),
+                part,
+                expected: answers.get(input, part).cloned(),
+                actual: solution.solve_dyn(parsed.as_ref(), part),
+            });
+        }
+    }
+    Ok(checks)
+}
+
+/// Record the actual answers of the checks in the answers.toml of dir,
+/// returns the checks whose answer was new or changed
+pub fn bless<P>(dir: P, checks: &[Check])
+    -> Result<Vec<&Check>, AnswersError>
+where
+    P: AsRef<Path>,
+{
+    let path = dir.as_ref().join(ANSWERS_FILE);
+    let mut answers = Answers::load(&path)?;
+    let changed: Vec<&Check> = checks.iter()
+        .filter(|x| { answers.set(&x.input, x.part, x.actual.clone()) })
+        .collect();
+    if !changed.is_empty() {
+        answers.save(&path)?;
+    }
+    Ok(changed)
+}
+
+/// For the tests of a day, panics with a diff of every wrong or missing
+/// answer of the inputs in dir
+pub fn assert_answers<P>(solution: &dyn DynSolution, dir: P)
+where
+    P: AsRef<Path>,
+{
+    let checks = check_day(solution, dir, &Part::BOTH).expect("couldn't read answers");
+    assert!(!checks.is_empty(), "no inputs to check");
+    let failed: Vec<String> = checks.iter()
+        .filter(|x| { !x.is_ok() })
+        .map(|x| { format!("{}\n{}", x, x.diff()) })
+        .collect();
+    assert!(failed.is_empty(), "day {}, record new answers with aoc --bless\n{}",
+        solution.day(), failed.concat());
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    /// test answers survive writing and reading back
+    fn test_0x001()
+    {
+        let mut answers = Answers::new();
+        assert!(answers.set("input", Part::One, Answer::from(1722)));
+        assert!(!answers.set("input", Part::One, Answer::from("1722")));
+        answers.set("input", Part::Two, Answer::from(u64::MAX));
+        answers.set("test_input", Part::Two, Answer::from("two\nlines"));
+        let text = answers.to_string();
+        assert!(text.starts_with("[input]\npart1 = 1722\npart2 = \"18446744073709551615\"\n"));
+        let parsed = Answers::parse(&text).unwrap();
+        assert_eq!(parsed.get("input", Part::One), Some(&Answer::from(1722)));
+        assert_eq!(parsed.get("test_input", Part::Two), Some(&Answer::from("two\nlines")));
+        assert_eq!(parsed.get("test_input", Part::One), None);
+
+        assert!(matches!(Answers::parse("[input]\npart3 = 1"), Err(AnswersError::Invalid { .. })));
+        assert!(matches!(Answers::parse("[input]\npart1 = 1.5"), Err(AnswersError::Invalid { .. })));
+        assert!(matches!(Answers::parse("[input"), Err(AnswersError::Parse(_))));
+        assert!(Answers::load("no/such/answers.toml").unwrap().is_empty());
+    }
+
+    #[test]
+    /// test wrong answers are reported as a diff
+    fn test_0x002()
+    {
+        let check = Check {
+            input: "input".to_string(),
+            part: Part::Two,
+            expected: Some(Answer::from(19081)),
+            actual: Answer::from(19080),
+        };
+        assert!(!check.is_ok());
+        assert_eq!(check.to_string(), "input.txt part2: wrong");
+        assert_eq!(check.diff(), "- 19081\n+ 19080\n");
+
+        let check = Check {
+            expected: Some(Answer::from("#.#\n.#.")),
+            actual: Answer::from("#.#\n##."),
+            ..check
+        };
+        assert_eq!(check.diff(), "  #.#\n- .#.\n+ ##.\n");
+
+        let check = Check { expected: None, ..check };
+        assert_eq!(check.to_string(), "input.txt part2: not recorded");
+        let check = Check { expected: Some(Answer::from(5)), actual: Answer::from("5"), ..check };
+        assert!(check.is_ok());
+    }
+}
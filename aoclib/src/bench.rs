@@ -0,0 +1,215 @@
+//! Benchmarks of the days, parse and each part timed on their own
+
+mod alloc;
+
+pub use alloc::{
+    is_counting, CountingAlloc,
+};
+
+use serde_json::json;
+use std::hint::black_box;
+use std::time::Instant;
+
+use super::solution::{
+    DynSolution, Part,
+};
+
+/// Timings of one stage over every iteration, in nanoseconds
+/// Allocations are per iteration, None without CountingAlloc
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stats
+{
+    pub iterations: usize,
+    pub min: u64,
+    pub median: u64,
+    pub p95: u64,
+    pub allocations: Option<u64>,
+    pub allocated_bytes: Option<u64>,
+}
+
+impl Stats
+{
+    /// Panics without samples
+    fn new(mut samples: Vec<u64>, allocations: Option<(u64, u64)>)
+        -> Stats
+    {
+        assert!(!samples.is_empty(), "no samples");
+        samples.sort_unstable();
+        let iterations = samples.len();
+        // nearest rank, the smallest sample with at least p percent at or below it
+        let percentile = |p: usize| { samples[(p * iterations).div_ceil(100).max(1) - 1] };
+        Stats {
+            iterations,
+            min: samples[0],
+            median: percentile(50),
+            p95: percentile(95),
+            allocations: allocations.map(|(x, _)| { x / iterations as u64 }),
+            allocated_bytes: allocations.map(|(_, x)| { x / iterations as u64 }),
+        }
+    }
+
+    pub fn to_json(&self)
+        -> serde_json::Value
+    {
+        json!({
+            "iterations": self.iterations,
+            "min_ns": self.min,
+            "median_ns": self.median,
+            "p95_ns": self.p95,
+            "allocations": self.allocations,
+            "allocated_bytes": self.allocated_bytes,
+        })
+    }
+}
+
+/// Time f for each iteration, counting what it allocates if the
+/// counting allocator is in use
+/// Whatever f returns is dropped outside of the timing
+fn measure<T, F>(iterations: usize, mut f: F)
+    -> (Stats, T)
+where
+    F: FnMut() -> T,
+{
+    let iterations = iterations.max(1);
+    let counting = is_counting();
+    let mut samples = Vec::with_capacity(iterations);
+    let mut last = None;
+    let (allocations_before, bytes_before) = alloc::snapshot();
+    for _ in 0..iterations {
+        let now = Instant::now();
+        let result = black_box(f());
+        samples.push(now.elapsed().as_nanos() as u64);
+        last = Some(result);
+    }
+    let (allocations, bytes) = alloc::snapshot();
+    // samples has its room up front, so only f allocates in the loop
+    let allocations = counting.then_some((allocations - allocations_before, bytes - bytes_before));
+    (Stats::new(samples, allocations), last.expect("at least one iteration"))
+}
+
+/// Benchmark of every stage of one day
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayBench
+{
+    pub day: u32,
+    pub parse: Stats,
+    pub part1: Stats,
+    pub part2: Stats,
+}
+
+impl DayBench
+{
+    pub fn stages(&self)
+        -> [(&'static str, &Stats); 3]
+    {
+        [("parse", &self.parse), ("part1", &self.part1), ("part2", &self.part2)]
+    }
+
+    pub fn to_json(&self)
+        -> serde_json::Value
+    {
+        let mut value = json!({ "day": self.day });
+        for (stage, stats) in self.stages() {
+            value[stage] = stats.to_json();
+        }
+        value
+    }
+}
+
+/// Parse the input and solve both parts, iterations times each,
+/// the parts all use the input of the last parse
+pub fn bench_day(solution: &dyn DynSolution, input: &str, iterations: usize)
+    -> DayBench
+{
+    let (parse, parsed) = measure(iterations, | | { solution.parse_dyn(input) });
+    let (part1, _) = measure(iterations, | | { solution.solve_dyn(parsed.as_ref(), Part::One) });
+    let (part2, _) = measure(iterations, | | { solution.solve_dyn(parsed.as_ref(), Part::Two) });
+    DayBench { day: solution.day(), parse, part1, part2 }
+}
+
+/// Every benchmark as one JSON document, to compare between commits
+pub fn to_json(benches: &[DayBench])
+    -> String
+{
+    let days: Vec<serde_json::Value> = benches.iter().map(DayBench::to_json).collect();
+    let value = json!({
+        "counting_allocations": is_counting(),
+        "days": days,
+    });
+    serde_json::to_string_pretty(&value).expect("json of numbers and strings")
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::solution::{Answer, Solution};
+
+    struct Sum;
+
+    impl Solution for Sum
+    {
+        type Input = Vec<u64>;
+
+        fn day(&self)
+            -> u32
+        {
+            0
+        }
+
+        fn parse(&self, input: &str)
+            -> Vec<u64>
+        {
+            input.split(',').map(|x| { x.parse().unwrap() }).collect()
+        }
+
+        fn part1(&self, input: &Vec<u64>)
+            -> Answer
+        {
+            input.iter().sum::<u64>().into()
+        }
+
+        fn part2(&self, input: &Vec<u64>)
+            -> Answer
+        {
+            input.len().into()
+        }
+    }
+
+    #[test]
+    /// test min, median and p95 of the samples
+    fn test_0x001()
+    {
+        let stats = Stats::new((1..=100).rev().collect(), None);
+        assert_eq!((stats.iterations, stats.min, stats.median, stats.p95), (100, 1, 50, 95));
+
+        let stats = Stats::new(vec![7, 3, 5], Some((30, 300)));
+        assert_eq!((stats.min, stats.median, stats.p95), (3, 5, 7));
+        assert_eq!((stats.allocations, stats.allocated_bytes), (Some(10), Some(100)));
+
+        let stats = Stats::new(vec![4], None);
+        assert_eq!((stats.min, stats.median, stats.p95), (4, 4, 4));
+    }
+
+    #[test]
+    /// test every stage is run and written to json, without counting
+    /// allocations as the tests use the system allocator
+    fn test_0x002()
+    {
+        assert!(!is_counting());
+        let bench = bench_day(&Sum, "1,2,3", 5);
+        assert_eq!(bench.day, 0);
+        for (_, stats) in bench.stages() {
+            assert_eq!(stats.iterations, 5);
+            assert!(stats.min <= stats.median && stats.median <= stats.p95);
+            assert_eq!(stats.allocations, None);
+        }
+
+        let json = to_json(std::slice::from_ref(&bench));
+        let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(json["counting_allocations"], false);
+        assert_eq!(json["days"][0]["day"], 0);
+        assert_eq!(json["days"][0]["part2"]["p95_ns"], bench.part2.p95);
+        assert!(json["days"][0]["parse"]["allocations"].is_null());
+    }
+}
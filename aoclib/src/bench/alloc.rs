@@ -0,0 +1,70 @@
+//! Global allocator that counts allocations, for the allocation columns
+//! of the benchmarks
+
+use std::alloc::{
+    GlobalAlloc, Layout, System,
+};
+use std::hint::black_box;
+use std::sync::atomic::{
+    AtomicU64, Ordering,
+};
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// The system allocator, counting every allocation and reallocation
+/// of the whole process
+/// A binary opts in with
+/// `#[global_allocator] static ALLOCATOR: CountingAlloc = CountingAlloc;`
+pub struct CountingAlloc;
+
+fn count(size: usize)
+{
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    ALLOCATED_BYTES.fetch_add(size as u64, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for CountingAlloc
+{
+    unsafe fn alloc(&self, layout: Layout)
+        -> *mut u8
+    {
+        count(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout)
+        -> *mut u8
+    {
+        count(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize)
+        -> *mut u8
+    {
+        count(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout)
+    {
+        System.dealloc(ptr, layout)
+    }
+}
+
+/// Allocations and bytes allocated so far
+pub(super) fn snapshot()
+    -> (u64, u64)
+{
+    (ALLOCATIONS.load(Ordering::Relaxed), ALLOCATED_BYTES.load(Ordering::Relaxed))
+}
+
+/// False unless the binary uses CountingAlloc
+pub fn is_counting()
+    -> bool
+{
+    let (before, _) = snapshot();
+    drop(black_box(Box::new(0u64)));
+    snapshot().0 != before
+}
@@ -0,0 +1,144 @@
+//! Conversions between binary text, bit vectors and integers,
+//! and between integers and text in other radixes
+//! Bits are u8 0 or 1, most significant first
+
+mod bitset;
+mod bitvec;
+mod radix;
+mod reader;
+pub use bitset::BitSet;
+pub use bitvec::{
+    BitMatrix, BitVec,
+};
+pub use radix::{
+    format_big, format_radix, hex_to_bitstr, parse_big, parse_radix,
+};
+pub use reader::BitReader;
+
+use num::PrimInt;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BitError
+{
+    /// a character that is not a digit of the radix at the given char index
+    InvalidChar { position: usize, found: char },
+    /// a bit other than 0 or 1 at the given index
+    InvalidBit { position: usize, found: u8 },
+    /// the value doesn't fit in the requested integer type
+    Overflow,
+    /// a read of more bits than are left, position counts from the stream start
+    Underflow { position: usize, wanted: usize, remaining: usize },
+    /// a row of a matrix is wider or narrower than the first one
+    WidthMismatch { row: usize, expected: usize, found: usize },
+}
+
+impl fmt::Display for BitError
+{
+    fn fmt(&self, f: &mut fmt::Formatter)
+        -> fmt::Result
+    {
+        match self {
+            BitError::InvalidChar { position, found } =>
+                write!(f, "invalid digit {:?} at {}", found, position),
+            BitError::InvalidBit { position, found } =>
+                write!(f, "invalid bit {} at {}", found, position),
+            BitError::Overflow => write!(f, "number too large for the integer type"),
+            BitError::Underflow { position, wanted, remaining } =>
+                write!(f, "read of {} bits at {} with only {} left", wanted, position, remaining),
+            BitError::WidthMismatch { row, expected, found } =>
+                write!(f, "row {} has {} bits, expected {}", row, found, expected),
+        }
+    }
+}
+
+impl Error for BitError {}
+
+fn char_to_bit(position: usize, c: char)
+    -> Result<u8, BitError>
+{
+    match c {
+        '0' => Ok(0),
+        '1' => Ok(1),
+        found => Err(BitError::InvalidChar { position, found }),
+    }
+}
+
+/// Shift bit in below acc
+fn push_bit<T>(acc: T, bit: u8)
+    -> Result<T, BitError>
+where
+    T: PrimInt,
+{
+    let bit = if bit == 1 { T::one() } else { T::zero() };
+    acc.checked_mul(&(T::one() + T::one()))
+        .and_then(|x| { x.checked_add(&bit) })
+        .ok_or(BitError::Overflow)
+}
+
+pub fn bitvec_to_num<T>(bits: &[u8])
+    -> Result<T, BitError>
+where
+    T: PrimInt,
+{
+    bits.iter().enumerate().try_fold(T::zero(), |acc, (position, &bit)| {
+        match bit {
+            0 | 1 => push_bit(acc, bit),
+            found => Err(BitError::InvalidBit { position, found }),
+        }
+    })
+}
+
+pub fn bitstr_to_vec(line: &str)
+    -> Result<Vec<u8>, BitError>
+{
+    line.chars()
+        .enumerate()
+        .map(|(position, x)| { char_to_bit(position, x) })
+        .collect()
+}
+
+pub fn bitstr_to_num<T>(bitstr: &str)
+    -> Result<T, BitError>
+where
+    T: PrimInt,
+{
+    parse_radix(bitstr, 2)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    /// test conversions agree for every integer width
+    fn test_0x001()
+    {
+        assert_eq!(bitstr_to_vec("10110"), Ok(vec![1, 0, 1, 1, 0]));
+        assert_eq!(bitvec_to_num::<u8>(&[1, 0, 1, 1, 0]), Ok(22));
+        assert_eq!(bitstr_to_num::<u8>("10110"), Ok(22));
+        assert_eq!(bitstr_to_num::<i32>("10110"), Ok(22));
+        assert_eq!(bitstr_to_num::<u128>(&"1".repeat(128)), Ok(u128::MAX));
+        assert_eq!(bitstr_to_num::<u64>(""), Ok(0));
+        // leading zeros don't count towards the width
+        assert_eq!(bitstr_to_num::<u8>(&format!("{}11111111", "0".repeat(100))), Ok(255));
+    }
+
+    #[test]
+    /// test too wide numbers and invalid input are errors
+    fn test_0x002()
+    {
+        assert_eq!(bitstr_to_num::<u8>("100000000"), Err(BitError::Overflow));
+        assert_eq!(bitstr_to_num::<i32>(&format!("1{}", "0".repeat(31))), Err(BitError::Overflow));
+        assert_eq!(bitstr_to_num::<u32>(&format!("1{}", "0".repeat(31))), Ok(1 << 31));
+        assert_eq!(bitvec_to_num::<u32>(&[1; 33]), Err(BitError::Overflow));
+
+        assert_eq!(bitstr_to_vec("10x1"), Err(BitError::InvalidChar { position: 2, found: 'x' }));
+        assert_eq!(bitstr_to_num::<u8>("1 0"), Err(BitError::InvalidChar { position: 1, found: ' ' }));
+        assert_eq!(bitvec_to_num::<u8>(&[1, 2]), Err(BitError::InvalidBit { position: 1, found: 2 }));
+        assert_eq!(BitError::InvalidChar { position: 2, found: 'x' }.to_string(),
+            "invalid digit 'x' at 2");
+    }
+}
@@ -0,0 +1,302 @@
+//! Sets of small integers, one bit per possible element
+
+use std::fmt;
+use std::ops::{
+    BitAnd, BitOr, BitXor, Sub,
+};
+
+/// Set of integers in 0 .. N, N is at most 128
+/// Copy and hashable, so it works as a key, e.g. for lit display segments
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BitSet<const N: usize>
+{
+    bits: u128,
+}
+
+impl<const N: usize> BitSet<N>
+{
+    const FITS: () = assert!(N <= 128, "BitSet holds at most 128 elements");
+
+    const MASK: u128 = if N == 128 { u128::MAX } else { (1 << N) - 1 };
+
+    pub fn new()
+        -> BitSet<N>
+    {
+        // fails the build for N above 128
+        let () = Self::FITS;
+        BitSet { bits: 0 }
+    }
+
+    /// Every element of 0 .. N
+    pub fn full()
+        -> BitSet<N>
+    {
+        BitSet::from_bits(u128::MAX)
+    }
+
+    /// Element i is in the set if bit i is set, bits from N up are dropped
+    pub fn from_bits(bits: u128)
+        -> BitSet<N>
+    {
+        let mut set = BitSet::new();
+        set.bits = bits & Self::MASK;
+        set
+    }
+
+    pub fn bits(&self)
+        -> u128
+    {
+        self.bits
+    }
+
+    fn bit(element: usize)
+        -> u128
+    {
+        // every element goes through here, however the set was made
+        let () = Self::FITS;
+        assert!(element < N, "{} is out of range for BitSet<{}>", element, N);
+        1 << element
+    }
+
+    /// Returns true if element was not in the set yet
+    pub fn insert(&mut self, element: usize)
+        -> bool
+    {
+        let inserted = !self.contains(element);
+        self.bits |= Self::bit(element);
+        inserted
+    }
+
+    /// Returns true if element was in the set
+    pub fn remove(&mut self, element: usize)
+        -> bool
+    {
+        let removed = self.contains(element);
+        self.bits &= !Self::bit(element);
+        removed
+    }
+
+    pub fn contains(&self, element: usize)
+        -> bool
+    {
+        self.bits & Self::bit(element) != 0
+    }
+
+    pub fn len(&self)
+        -> usize
+    {
+        self.bits.count_ones() as usize
+    }
+
+    pub fn is_empty(&self)
+        -> bool
+    {
+        self.bits == 0
+    }
+
+    pub fn union(&self, other: &BitSet<N>)
+        -> BitSet<N>
+    {
+        BitSet { bits: self.bits | other.bits }
+    }
+
+    pub fn intersection(&self, other: &BitSet<N>)
+        -> BitSet<N>
+    {
+        BitSet { bits: self.bits & other.bits }
+    }
+
+    /// Elements of self that are not in other
+    pub fn difference(&self, other: &BitSet<N>)
+        -> BitSet<N>
+    {
+        BitSet { bits: self.bits & !other.bits }
+    }
+
+    pub fn symmetric_difference(&self, other: &BitSet<N>)
+        -> BitSet<N>
+    {
+        BitSet { bits: self.bits ^ other.bits }
+    }
+
+    /// Elements of 0 .. N that are not in self
+    pub fn complement(&self)
+        -> BitSet<N>
+    {
+        BitSet { bits: !self.bits & Self::MASK }
+    }
+
+    pub fn is_subset(&self, other: &BitSet<N>)
+        -> bool
+    {
+        self.bits & !other.bits == 0
+    }
+
+    pub fn is_superset(&self, other: &BitSet<N>)
+        -> bool
+    {
+        other.is_subset(self)
+    }
+
+    pub fn is_disjoint(&self, other: &BitSet<N>)
+        -> bool
+    {
+        self.bits & other.bits == 0
+    }
+
+    /// Elements in increasing order
+    pub fn iter(&self)
+        -> impl Iterator<Item = usize>
+    {
+        let mut bits = self.bits;
+        std::iter::from_fn(move | | {
+            if bits == 0 {
+                return None;
+            }
+            let element = bits.trailing_zeros() as usize;
+            bits &= bits - 1;
+            Some(element)
+        })
+    }
+}
+
+/// Not derived, so the size check of new is not skipped
+impl<const N: usize> Default for BitSet<N>
+{
+    fn default()
+        -> BitSet<N>
+    {
+        BitSet::new()
+    }
+}
+
+impl<const N: usize> FromIterator<usize> for BitSet<N>
+{
+    fn from_iter<I>(elements: I)
+        -> BitSet<N>
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        let mut set = BitSet::new();
+        set.extend(elements);
+        set
+    }
+}
+
+impl<const N: usize> Extend<usize> for BitSet<N>
+{
+    fn extend<I>(&mut self, elements: I)
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        for element in elements {
+            self.insert(element);
+        }
+    }
+}
+
+impl<const N: usize> fmt::Debug for BitSet<N>
+{
+    fn fmt(&self, f: &mut fmt::Formatter)
+        -> fmt::Result
+    {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<const N: usize> BitOr for BitSet<N>
+{
+    type Output = BitSet<N>;
+
+    fn bitor(self, rhs: BitSet<N>)
+        -> BitSet<N>
+    {
+        self.union(&rhs)
+    }
+}
+
+impl<const N: usize> BitAnd for BitSet<N>
+{
+    type Output = BitSet<N>;
+
+    fn bitand(self, rhs: BitSet<N>)
+        -> BitSet<N>
+    {
+        self.intersection(&rhs)
+    }
+}
+
+impl<const N: usize> BitXor for BitSet<N>
+{
+    type Output = BitSet<N>;
+
+    fn bitxor(self, rhs: BitSet<N>)
+        -> BitSet<N>
+    {
+        self.symmetric_difference(&rhs)
+    }
+}
+
+impl<const N: usize> Sub for BitSet<N>
+{
+    type Output = BitSet<N>;
+
+    fn sub(self, rhs: BitSet<N>)
+        -> BitSet<N>
+    {
+        self.difference(&rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    /// test inserting, removing and iterating
+    fn test_0x001()
+    {
+        let mut set: BitSet<10> = BitSet::new();
+        assert!(set.insert(3));
+        assert!(!set.insert(3));
+        set.extend([9, 0]);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![0, 3, 9]);
+        assert_eq!(set.len(), 3);
+        assert!(set.remove(0));
+        assert!(!set.remove(0));
+        assert_eq!(format!("{:?}", set), "{3, 9}");
+
+        assert_eq!(BitSet::<10>::full().len(), 10);
+        assert_eq!(BitSet::<128>::full().complement(), BitSet::new());
+        assert_eq!(BitSet::<4>::from_bits(0xff), BitSet::full());
+        assert_eq!(BitSet::<10>::default(), BitSet::new());
+    }
+
+    #[test]
+    /// test set algebra
+    fn test_0x002()
+    {
+        let a: BitSet<7> = [0, 1, 2, 3].into_iter().collect();
+        let b: BitSet<7> = [2, 3, 4].into_iter().collect();
+        assert_eq!((a | b).iter().collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+        assert_eq!((a & b).iter().collect::<Vec<_>>(), vec![2, 3]);
+        assert_eq!((a - b).iter().collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!((a ^ b).iter().collect::<Vec<_>>(), vec![0, 1, 4]);
+        assert_eq!(a.complement().iter().collect::<Vec<_>>(), vec![4, 5, 6]);
+
+        assert!((a & b).is_subset(&a) && a.is_superset(&(a & b)));
+        assert!(!b.is_subset(&a));
+        assert!((a - b).is_disjoint(&b));
+        assert!(BitSet::<7>::new().is_subset(&b));
+    }
+
+    #[test]
+    #[should_panic]
+    /// test elements past N are rejected
+    fn test_0x003()
+    {
+        let mut set: BitSet<7> = BitSet::new();
+        set.insert(7);
+    }
+}
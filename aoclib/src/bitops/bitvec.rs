@@ -0,0 +1,553 @@
+//! Bits packed into u64 words, bit i of a vector is bit i % 64 of word i / 64
+//! Bits past the length are always zero
+
+use num::PrimInt;
+use std::fmt;
+use std::ops::{
+    BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not,
+};
+use std::str::FromStr;
+
+use super::{
+    char_to_bit, push_bit, radix, BitError,
+};
+
+const WORD_BITS: usize = 64;
+
+fn words_for(len: usize)
+    -> usize
+{
+    len.div_ceil(WORD_BITS)
+}
+
+/// Index of every set bit in words, lowest first
+fn iter_ones(words: &[u64])
+    -> impl Iterator<Item = usize> + '_
+{
+    words.iter().enumerate().flat_map(|(i, &word)| {
+        let mut word = word;
+        std::iter::from_fn(move | | {
+            if word == 0 {
+                return None;
+            }
+            let bit = word.trailing_zeros() as usize;
+            word &= word - 1;
+            Some(i * WORD_BITS + bit)
+        })
+    })
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BitVec
+{
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitVec
+{
+    pub fn new()
+        -> BitVec
+    {
+        BitVec::default()
+    }
+
+    pub fn zeros(len: usize)
+        -> BitVec
+    {
+        BitVec { words: vec![0; words_for(len)], len }
+    }
+
+    pub fn ones(len: usize)
+        -> BitVec
+    {
+        let mut bits = BitVec { words: vec![u64::MAX; words_for(len)], len };
+        bits.clear_tail();
+        bits
+    }
+
+    /// Keep the bits past len zero, so words can be compared and counted
+    fn clear_tail(&mut self)
+    {
+        let used = self.len % WORD_BITS;
+        if used != 0 {
+            if let Some(last) = self.words.last_mut() {
+                *last &= (1 << used) - 1;
+            }
+        }
+    }
+
+    pub fn from_bits(bits: &[u8])
+        -> Result<BitVec, BitError>
+    {
+        let mut vec = BitVec::zeros(bits.len());
+        for (position, &bit) in bits.iter().enumerate() {
+            match bit {
+                0 => {},
+                1 => vec.set(position, true),
+                found => return Err(BitError::InvalidBit { position, found }),
+            }
+        }
+        Ok(vec)
+    }
+
+    /// Four bits per hex digit, the most significant first
+    pub fn from_hex(hex: &str)
+        -> Result<BitVec, BitError>
+    {
+        let digits = radix::digits(hex, 16)?;
+        let mut vec = BitVec::zeros(digits.len() * 4);
+        for (position, digit) in digits.into_iter().enumerate() {
+            for bit in 0..4 {
+                if digit >> (3 - bit) & 1 == 1 {
+                    vec.set(position * 4 + bit, true);
+                }
+            }
+        }
+        Ok(vec)
+    }
+
+    pub fn len(&self)
+        -> usize
+    {
+        self.len
+    }
+
+    pub fn is_empty(&self)
+        -> bool
+    {
+        self.len == 0
+    }
+
+    /// The packed words, see the module docs for the layout
+    pub fn words(&self)
+        -> &[u64]
+    {
+        &self.words
+    }
+
+    pub fn get(&self, index: usize)
+        -> bool
+    {
+        assert!(index < self.len, "bit {} out of range for length {}", index, self.len);
+        self.words[index / WORD_BITS] >> (index % WORD_BITS) & 1 == 1
+    }
+
+    pub fn set(&mut self, index: usize, value: bool)
+    {
+        assert!(index < self.len, "bit {} out of range for length {}", index, self.len);
+        let mask = 1 << (index % WORD_BITS);
+        if value {
+            self.words[index / WORD_BITS] |= mask;
+        } else {
+            self.words[index / WORD_BITS] &= !mask;
+        }
+    }
+
+    pub fn push(&mut self, value: bool)
+    {
+        if self.len.is_multiple_of(WORD_BITS) {
+            self.words.push(0);
+        }
+        self.len += 1;
+        self.set(self.len - 1, value);
+    }
+
+    pub fn count_ones(&self)
+        -> usize
+    {
+        self.words.iter().map(|x| { x.count_ones() as usize }).sum()
+    }
+
+    pub fn count_zeros(&self)
+        -> usize
+    {
+        self.len - self.count_ones()
+    }
+
+    pub fn iter(&self)
+        -> impl Iterator<Item = bool> + '_
+    {
+        (0..self.len).map(|x| { self.get(x) })
+    }
+
+    /// Index of every set bit, lowest first
+    pub fn iter_ones(&self)
+        -> impl Iterator<Item = usize> + '_
+    {
+        iter_ones(&self.words)
+    }
+
+    /// Read the bits as a number, bit 0 is the most significant
+    pub fn to_num<T>(&self)
+        -> Result<T, BitError>
+    where
+        T: PrimInt,
+    {
+        self.iter().try_fold(T::zero(), |acc, x| { push_bit(acc, x as u8) })
+    }
+}
+
+impl FromStr for BitVec
+{
+    type Err = BitError;
+
+    fn from_str(bitstr: &str)
+        -> Result<BitVec, BitError>
+    {
+        let mut vec = BitVec::zeros(bitstr.chars().count());
+        for (position, x) in bitstr.chars().enumerate() {
+            if char_to_bit(position, x)? == 1 {
+                vec.set(position, true);
+            }
+        }
+        Ok(vec)
+    }
+}
+
+impl fmt::Display for BitVec
+{
+    fn fmt(&self, f: &mut fmt::Formatter)
+        -> fmt::Result
+    {
+        for bit in self.iter() {
+            f.write_str(if bit { "1" } else { "0" })?;
+        }
+        Ok(())
+    }
+}
+
+impl Not for &BitVec
+{
+    type Output = BitVec;
+
+    fn not(self)
+        -> BitVec
+    {
+        let mut bits = BitVec {
+            words: self.words.iter().map(|x| { !x }).collect(),
+            len: self.len,
+        };
+        bits.clear_tail();
+        bits
+    }
+}
+
+/// Word wise operators, both sides must have the same length
+macro_rules! impl_bit_op {
+    ($op:ident, $method:ident, $op_assign:ident, $method_assign:ident) => {
+        impl $op_assign<&BitVec> for BitVec
+        {
+            fn $method_assign(&mut self, rhs: &BitVec)
+            {
+                assert_eq!(self.len, rhs.len, "bit vectors differ in length");
+                for (x, y) in self.words.iter_mut().zip(&rhs.words) {
+                    x.$method_assign(y);
+                }
+            }
+        }
+
+        impl $op<&BitVec> for &BitVec
+        {
+            type Output = BitVec;
+
+            fn $method(self, rhs: &BitVec)
+                -> BitVec
+            {
+                let mut bits = self.clone();
+                bits.$method_assign(rhs);
+                bits
+            }
+        }
+    };
+}
+
+impl_bit_op!(BitAnd, bitand, BitAndAssign, bitand_assign);
+impl_bit_op!(BitOr, bitor, BitOrAssign, bitor_assign);
+impl_bit_op!(BitXor, bitxor, BitXorAssign, bitxor_assign);
+
+/// Rows of equal width, each row packed like a BitVec
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BitMatrix
+{
+    rows: usize,
+    cols: usize,
+    /// words per row
+    stride: usize,
+    words: Vec<u64>,
+}
+
+impl BitMatrix
+{
+    pub fn new(rows: usize, cols: usize)
+        -> BitMatrix
+    {
+        let stride = words_for(cols);
+        BitMatrix { rows, cols, stride, words: vec![0; rows * stride] }
+    }
+
+    /// The first row decides the width, the others have to match it
+    pub fn from_rows<I>(rows: I)
+        -> Result<BitMatrix, BitError>
+    where
+        I: IntoIterator<Item = BitVec>,
+    {
+        let mut matrix = BitMatrix::default();
+        for (row, bits) in rows.into_iter().enumerate() {
+            if row == 0 {
+                matrix.cols = bits.len();
+                matrix.stride = words_for(bits.len());
+            } else if bits.len() != matrix.cols {
+                return Err(BitError::WidthMismatch {
+                    row,
+                    expected: matrix.cols,
+                    found: bits.len(),
+                });
+            }
+            matrix.words.extend(&bits.words);
+            matrix.rows += 1;
+        }
+        Ok(matrix)
+    }
+
+    /// One row per line of '0' and '1'
+    pub fn parse_lines<I, S>(lines: I)
+        -> Result<BitMatrix, BitError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let rows: Vec<BitVec> = lines.into_iter()
+            .map(|x| { x.as_ref().parse() })
+            .collect::<Result<_, _>>()?;
+        BitMatrix::from_rows(rows)
+    }
+
+    pub fn rows(&self)
+        -> usize
+    {
+        self.rows
+    }
+
+    pub fn cols(&self)
+        -> usize
+    {
+        self.cols
+    }
+
+    fn row_words(&self, row: usize)
+        -> &[u64]
+    {
+        &self.words[row * self.stride .. (row + 1) * self.stride]
+    }
+
+    fn check(&self, row: usize, col: usize)
+    {
+        assert!(row < self.rows && col < self.cols,
+            "({}, {}) out of range for {}x{} matrix", row, col, self.rows, self.cols);
+    }
+
+    pub fn get(&self, row: usize, col: usize)
+        -> bool
+    {
+        self.check(row, col);
+        self.words[row * self.stride + col / WORD_BITS] >> (col % WORD_BITS) & 1 == 1
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, value: bool)
+    {
+        self.check(row, col);
+        let word = &mut self.words[row * self.stride + col / WORD_BITS];
+        let mask = 1 << (col % WORD_BITS);
+        if value {
+            *word |= mask;
+        } else {
+            *word &= !mask;
+        }
+    }
+
+    pub fn row(&self, row: usize)
+        -> BitVec
+    {
+        assert!(row < self.rows, "row {} out of range for {} rows", row, self.rows);
+        BitVec { words: self.row_words(row).to_vec(), len: self.cols }
+    }
+
+    /// Bit col of every row, for many columns transpose once instead
+    pub fn column(&self, col: usize)
+        -> BitVec
+    {
+        let mut bits = BitVec::zeros(self.rows);
+        for row in 0..self.rows {
+            if self.get(row, col) {
+                bits.set(row, true);
+            }
+        }
+        bits
+    }
+
+    /// Number of set bits in each column
+    pub fn column_counts(&self)
+        -> Vec<usize>
+    {
+        let mut counts = vec![0; self.cols];
+        for row in 0..self.rows {
+            for col in iter_ones(self.row_words(row)) {
+                counts[col] += 1;
+            }
+        }
+        counts
+    }
+
+    pub fn transpose(&self)
+        -> BitMatrix
+    {
+        let mut transposed = BitMatrix::new(self.cols, self.rows);
+        for row in 0..self.rows {
+            for col in iter_ones(self.row_words(row)) {
+                transposed.set(col, row, true);
+            }
+        }
+        transposed
+    }
+
+    /// The rows whose bit is set in mask, which has one bit per row
+    pub fn select_rows(&self, mask: &BitVec)
+        -> BitMatrix
+    {
+        assert_eq!(mask.len(), self.rows, "mask needs one bit per row");
+        let mut selected = BitMatrix::new(0, self.cols);
+        for row in mask.iter_ones() {
+            selected.words.extend(self.row_words(row));
+            selected.rows += 1;
+        }
+        selected
+    }
+
+    /// The rows where bit col is value
+    pub fn filter_rows(&self, col: usize, value: bool)
+        -> BitMatrix
+    {
+        let column = self.column(col);
+        if value {
+            self.select_rows(&column)
+        } else {
+            self.select_rows(&!&column)
+        }
+    }
+
+    /// Every row and-ed with mask, which has one bit per column
+    pub fn mask_columns(&self, mask: &BitVec)
+        -> BitMatrix
+    {
+        assert_eq!(mask.len(), self.cols, "mask needs one bit per column");
+        let mut masked = self.clone();
+        for row in masked.words.chunks_mut(self.stride.max(1)) {
+            for (x, y) in row.iter_mut().zip(&mask.words) {
+                *x &= y;
+            }
+        }
+        masked
+    }
+}
+
+impl fmt::Display for BitMatrix
+{
+    fn fmt(&self, f: &mut fmt::Formatter)
+        -> fmt::Result
+    {
+        for row in 0..self.rows {
+            writeln!(f, "{}", self.row(row))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    /// test bit vector access, counting and operators across word boundaries
+    fn test_0x001()
+    {
+        let mut bits = BitVec::zeros(130);
+        for i in [0, 63, 64, 129] {
+            bits.set(i, true);
+        }
+        assert_eq!(bits.count_ones(), 4);
+        assert_eq!(bits.count_zeros(), 126);
+        assert_eq!(bits.iter_ones().collect::<Vec<_>>(), vec![0, 63, 64, 129]);
+        assert!(bits.get(64) && !bits.get(65));
+
+        let inverted = !&bits;
+        assert_eq!(inverted.count_ones(), 126);
+        assert_eq!(&inverted & &bits, BitVec::zeros(130));
+        assert_eq!(&inverted | &bits, BitVec::ones(130));
+        assert_eq!(&(&inverted ^ &BitVec::ones(130)), &bits);
+
+        let mut pushed = BitVec::new();
+        for bit in bits.iter() {
+            pushed.push(bit);
+        }
+        assert_eq!(pushed, bits);
+    }
+
+    #[test]
+    /// test parsing, printing and reading bit vectors as numbers
+    fn test_0x002()
+    {
+        let bits: BitVec = "10110".parse().unwrap();
+        assert_eq!(bits.to_string(), "10110");
+        assert_eq!(bits.to_num::<u8>(), Ok(22));
+        assert_eq!(BitVec::from_bits(&[1, 0, 1, 1, 0]), Ok(bits));
+        assert_eq!("12".parse::<BitVec>(), Err(BitError::InvalidChar { position: 1, found: '2' }));
+        assert_eq!(BitVec::from_hex("a5").unwrap().to_string(), "10100101");
+
+        let wide: BitVec = "1".repeat(3000).parse().unwrap();
+        assert_eq!(wide.count_ones(), 3000);
+        assert_eq!(wide.to_num::<u128>(), Err(BitError::Overflow));
+    }
+
+    #[test]
+    /// test column counts, transpose and row filters on the day 3 sample
+    fn test_0x003()
+    {
+        let lines = [
+            "00100", "11110", "10110", "10111", "10101", "01111",
+            "00111", "11100", "10000", "11001", "00010", "01010",
+        ];
+        let matrix = BitMatrix::parse_lines(lines).unwrap();
+        assert_eq!((matrix.rows(), matrix.cols()), (12, 5));
+        assert_eq!(matrix.column_counts(), vec![7, 5, 8, 7, 5]);
+
+        let transposed = matrix.transpose();
+        assert_eq!((transposed.rows(), transposed.cols()), (5, 12));
+        assert_eq!(transposed.row(0), matrix.column(0));
+        assert_eq!(transposed.transpose(), matrix);
+
+        let filtered = matrix.filter_rows(0, true).filter_rows(1, false);
+        assert_eq!(filtered.to_string(), "10110\n10111\n10101\n10000\n");
+        let masked = matrix.mask_columns(&"10001".parse().unwrap());
+        assert_eq!(masked.column_counts(), vec![7, 0, 0, 0, 5]);
+
+        assert_eq!(BitMatrix::parse_lines(["101", "1010"]),
+            Err(BitError::WidthMismatch { row: 1, expected: 3, found: 4 }));
+    }
+
+    #[test]
+    /// test rows wider than a word
+    fn test_0x004()
+    {
+        let mut rows = vec![BitVec::zeros(2000); 3];
+        rows[0].set(1999, true);
+        rows[2].set(64, true);
+        rows[2].set(1999, true);
+        let matrix = BitMatrix::from_rows(rows).unwrap();
+        let counts = matrix.column_counts();
+        assert_eq!((counts[64], counts[1999], counts.iter().sum::<usize>()), (1, 2, 3));
+        assert_eq!(matrix.filter_rows(1999, true).rows(), 2);
+        assert!(matrix.transpose().get(1999, 2));
+    }
+}
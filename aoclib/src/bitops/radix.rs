@@ -0,0 +1,130 @@
+//! Numbers as text in any radix from 2 to 36, digits past 9 are letters
+//! in either case, formatting uses lowercase like std
+
+use num::{
+    BigUint, PrimInt, Unsigned,
+};
+
+use super::{
+    BitError, BitVec,
+};
+
+fn check_radix(radix: u32)
+{
+    assert!((2..=36).contains(&radix), "radix {} is not in 2 ..= 36", radix);
+}
+
+/// Value of every digit, most significant first
+pub(super) fn digits(text: &str, radix: u32)
+    -> Result<Vec<u8>, BitError>
+{
+    check_radix(radix);
+    text.chars()
+        .enumerate()
+        .map(|(position, x)| {
+            x.to_digit(radix)
+                .map(|x| { x as u8 })
+                .ok_or(BitError::InvalidChar { position, found: x })
+        })
+        .collect()
+}
+
+/// Parse digits of radix into any integer type, checking for overflow
+pub fn parse_radix<T>(text: &str, radix: u32)
+    -> Result<T, BitError>
+where
+    T: PrimInt,
+{
+    let base = T::from(radix).ok_or(BitError::Overflow)?;
+    digits(text, radix)?.into_iter().try_fold(T::zero(), |acc, x| {
+        acc.checked_mul(&base)
+            .and_then(|acc| { acc.checked_add(&T::from(x)?) })
+            .ok_or(BitError::Overflow)
+    })
+}
+
+pub fn format_radix<T>(value: T, radix: u32)
+    -> String
+where
+    T: PrimInt + Unsigned,
+{
+    check_radix(radix);
+    let base = T::from(radix).expect("radix fits in every unsigned type");
+    let mut value = value;
+    let mut text = Vec::new();
+    loop {
+        let digit = (value % base).to_u32().expect("digit is below radix");
+        text.push(char::from_digit(digit, radix).expect("digit is below radix"));
+        value = value / base;
+        if value.is_zero() {
+            break;
+        }
+    }
+    text.iter().rev().collect()
+}
+
+/// Parse digits of radix without any limit on the size
+pub fn parse_big(text: &str, radix: u32)
+    -> Result<BigUint, BitError>
+{
+    let digits = digits(text, radix)?;
+    Ok(BigUint::from_radix_be(&digits, radix).expect("digits are below radix"))
+}
+
+pub fn format_big(value: &BigUint, radix: u32)
+    -> String
+{
+    check_radix(radix);
+    value.to_str_radix(radix)
+}
+
+/// Four '0' or '1' per hex digit, for the bitstr functions
+pub fn hex_to_bitstr(hex: &str)
+    -> Result<String, BitError>
+{
+    Ok(BitVec::from_hex(hex)?.to_string())
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::bitops::bitstr_to_num;
+
+    #[test]
+    /// test formatting and parsing back gives the same number in every radix
+    fn test_0x001()
+    {
+        let values = [0, 1, 35, 36, 255, 1 << 40, 0xdead_beef, u64::MAX];
+        for radix in 2..=36 {
+            for value in values {
+                let text = format_radix(value, radix);
+                assert_eq!(parse_radix::<u64>(&text, radix), Ok(value), "{} in radix {}", text, radix);
+                let big = BigUint::from(value);
+                assert_eq!(format_big(&big, radix), text);
+                assert_eq!(parse_big(&text, radix), Ok(big));
+            }
+        }
+        assert_eq!(format_radix(255u8, 16), "ff");
+        assert_eq!(format_radix(8u32, 8), "10");
+        assert_eq!(parse_radix::<i32>("Zz", 36), Ok(35 * 36 + 35));
+    }
+
+    #[test]
+    /// test big numbers, hex expansion and errors
+    fn test_0x002()
+    {
+        let text = "123456789".repeat(10);
+        let big = parse_big(&text, 10).unwrap();
+        assert_eq!(format_big(&big, 10), text);
+        let hex = format_big(&big, 16);
+        assert_eq!(parse_big(&hex_to_bitstr(&hex).unwrap(), 2), Ok(big));
+        assert_eq!(parse_radix::<u64>(&text, 10), Err(BitError::Overflow));
+
+        assert_eq!(hex_to_bitstr("D2fe28").unwrap(), "110100101111111000101000");
+        assert_eq!(bitstr_to_num::<u32>(&hex_to_bitstr("D2FE28").unwrap()), Ok(0xd2fe28));
+        assert_eq!(parse_radix::<u8>("777", 8), Err(BitError::Overflow));
+        assert_eq!(parse_radix::<u8>("19", 8), Err(BitError::InvalidChar { position: 1, found: '9' }));
+        assert_eq!(parse_big("-1", 10), Err(BitError::InvalidChar { position: 0, found: '-' }));
+    }
+}
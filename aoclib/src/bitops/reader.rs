@@ -0,0 +1,218 @@
+//! Sequential reads of bit fields, most significant bit first
+
+use num::PrimInt;
+use std::sync::Arc;
+
+use super::{
+    push_bit, BitError, BitVec,
+};
+
+/// Reads fields of any width from a stream of bits
+/// A sub stream covers the next bits of its parent and can't read past them,
+/// for formats where a header gives the length of what follows
+#[derive(Debug, Clone)]
+pub struct BitReader
+{
+    bits: Arc<BitVec>,
+    start: usize,
+    position: usize,
+    end: usize,
+}
+
+impl BitReader
+{
+    pub fn new(bits: BitVec)
+        -> BitReader
+    {
+        let end = bits.len();
+        BitReader { bits: Arc::new(bits), start: 0, position: 0, end }
+    }
+
+    /// Four bits per hex digit, like "D2FE28"
+    pub fn from_hex(hex: &str)
+        -> Result<BitReader, BitError>
+    {
+        Ok(BitReader::new(BitVec::from_hex(hex)?))
+    }
+
+    /// One bit per '0' or '1'
+    pub fn from_bitstr(bitstr: &str)
+        -> Result<BitReader, BitError>
+    {
+        Ok(BitReader::new(bitstr.parse()?))
+    }
+
+    /// Bits read so far from this stream
+    pub fn position(&self)
+        -> usize
+    {
+        self.position - self.start
+    }
+
+    pub fn remaining(&self)
+        -> usize
+    {
+        self.end - self.position
+    }
+
+    pub fn is_empty(&self)
+        -> bool
+    {
+        self.remaining() == 0
+    }
+
+    fn take(&mut self, count: usize)
+        -> Result<usize, BitError>
+    {
+        if count > self.remaining() {
+            return Err(BitError::Underflow {
+                position: self.position(),
+                wanted: count,
+                remaining: self.remaining(),
+            });
+        }
+        let from = self.position;
+        self.position += count;
+        Ok(from)
+    }
+
+    /// Next count bits as a number, fails without consuming anything
+    /// if fewer bits are left or the number doesn't fit in T
+    pub fn read<T>(&mut self, count: usize)
+        -> Result<T, BitError>
+    where
+        T: PrimInt,
+    {
+        let position = self.position;
+        let from = self.take(count)?;
+        let value = (from..from + count)
+            .try_fold(T::zero(), |acc, x| { push_bit(acc, self.bits.get(x) as u8) });
+        if value.is_err() {
+            self.position = position;
+        }
+        value
+    }
+
+    pub fn read_bit(&mut self)
+        -> Result<bool, BitError>
+    {
+        let from = self.take(1)?;
+        Ok(self.bits.get(from))
+    }
+
+    pub fn skip(&mut self, count: usize)
+        -> Result<(), BitError>
+    {
+        self.take(count)?;
+        Ok(())
+    }
+
+    /// Reader over the next length bits, this reader continues after them
+    pub fn sub_stream(&mut self, length: usize)
+        -> Result<BitReader, BitError>
+    {
+        let from = self.take(length)?;
+        Ok(BitReader {
+            bits: Arc::clone(&self.bits),
+            start: from,
+            position: from,
+            end: from + length,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    /// Version sum and value of a day 16 packet, literal 4 and sum 0 only
+    fn decode(reader: &mut BitReader)
+        -> Result<(u64, u64), BitError>
+    {
+        let mut version: u64 = reader.read(3)?;
+        let kind: u8 = reader.read(3)?;
+        if kind == 4 {
+            let mut value: u64 = 0;
+            loop {
+                let more = reader.read_bit()?;
+                value = value << 4 | reader.read::<u64>(4)?;
+                if !more {
+                    return Ok((version, value));
+                }
+            }
+        }
+
+        let mut values = Vec::new();
+        if reader.read_bit()? {
+            let count: usize = reader.read(11)?;
+            for _ in 0..count {
+                let (sub_version, value) = decode(reader)?;
+                version += sub_version;
+                values.push(value);
+            }
+        } else {
+            let length: usize = reader.read(15)?;
+            let mut sub_stream = reader.sub_stream(length)?;
+            while !sub_stream.is_empty() {
+                let (sub_version, value) = decode(&mut sub_stream)?;
+                version += sub_version;
+                values.push(value);
+            }
+        }
+        Ok((version, values.iter().sum()))
+    }
+
+    #[test]
+    /// test fields are read in order and the position follows
+    fn test_0x001()
+    {
+        let mut reader = BitReader::from_hex("D2FE28").unwrap();
+        assert_eq!(reader.remaining(), 24);
+        assert_eq!(reader.read::<u8>(3), Ok(6));
+        assert_eq!(reader.read::<u8>(3), Ok(4));
+        assert_eq!(reader.position(), 6);
+        assert_eq!(decode(&mut BitReader::from_hex("D2FE28").unwrap()), Ok((6, 2021)));
+
+        let mut reader = BitReader::from_bitstr("1011").unwrap();
+        assert_eq!(reader.read_bit(), Ok(true));
+        assert_eq!(reader.skip(1), Ok(()));
+        assert_eq!(reader.read::<u8>(2), Ok(3));
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    /// test nested length delimited and counted sub packets
+    fn test_0x002()
+    {
+        // two literals in a sub stream of 27 bits, then three counted ones
+        assert_eq!(decode(&mut BitReader::from_hex("38006F45291200").unwrap()), Ok((9, 30)));
+        assert_eq!(decode(&mut BitReader::from_hex("EE00D40C823060").unwrap()), Ok((14, 6)));
+        assert_eq!(decode(&mut BitReader::from_hex("8A004A801A8002F478").unwrap()).unwrap().0, 16);
+        assert_eq!(decode(&mut BitReader::from_hex("A0016C880162017C3686B18A3D4780").unwrap())
+            .unwrap().0, 31);
+    }
+
+    #[test]
+    /// test reading past the end of a stream or a sub stream is an error
+    fn test_0x003()
+    {
+        let mut reader = BitReader::from_bitstr("110100").unwrap();
+        let mut sub_stream = reader.sub_stream(4).unwrap();
+        assert_eq!(sub_stream.read::<u8>(3), Ok(6));
+        assert_eq!(sub_stream.read::<u8>(2),
+            Err(BitError::Underflow { position: 3, wanted: 2, remaining: 1 }));
+        // a failed read doesn't consume
+        assert_eq!(sub_stream.read::<u8>(1), Ok(1));
+        assert_eq!(reader.position(), 4);
+        assert_eq!(reader.read::<u8>(2), Ok(0));
+        assert!(reader.sub_stream(1).is_err());
+
+        let mut reader = BitReader::from_hex("FFFF").unwrap();
+        assert_eq!(reader.read::<u8>(9), Err(BitError::Overflow));
+        assert_eq!(reader.read::<u16>(16), Ok(u16::MAX));
+        let error = BitReader::from_hex("F0G").unwrap_err();
+        assert_eq!(error, BitError::InvalidChar { position: 2, found: 'G' });
+        assert_eq!(error.to_string(), "invalid digit 'G' at 2");
+    }
+}
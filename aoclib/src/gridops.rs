@@ -1,4 +1,7 @@
 use ndarray::prelude::*;
+use std::collections::{
+    BTreeMap, HashSet, VecDeque,
+};
 use std::path::Path;
 use super::fileops::get_file_lines;
 
@@ -10,11 +13,19 @@ where P: AsRef<Path>
     parse_lines(lines)
 }
 
-fn parse_lines(lines: impl Iterator<Item = String>)
+/// One row per line, one digit per cell
+pub fn parse_grid(text: &str)
     -> Array2<usize>
+{
+    parse_lines(text.lines())
+}
+
+fn parse_lines<S>(lines: impl Iterator<Item = S>)
+    -> Array2<usize>
+where S: AsRef<str>
 {
     let rows: Vec<Vec<usize>> = lines.map(|x| {
-            x.chars().map(|c| { c.to_digit(10).unwrap() as usize })
+            x.as_ref().chars().map(|c| { c.to_digit(10).unwrap() as usize })
                 .collect()
         }).collect();
     let h = rows.len();
@@ -76,6 +87,455 @@ pub fn find_sorounding(grid: &Array2<usize>, pos: (usize, usize))
     sorounding
 }
 
+/// Which cells count as neighbours of a cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity
+{
+    /// horizontally and vertically adjecent
+    Four,
+    /// adjecent including diagonals
+    Eight,
+}
+
+const FOUR_OFFSETS: [(i64, i64); 4] = [(-1,0), (1,0), (0,-1), (0,1)];
+const EIGHT_OFFSETS: [(i64, i64); 8] = [
+    (-1,-1), (-1,0), (-1,1),
+    ( 0,-1),         ( 0,1),
+    ( 1,-1), ( 1,0), ( 1,1),
+];
+
+/// Neighbours of pos inside a grid of dimension dim, without allocating
+pub fn neighbours(dim: (usize, usize), pos: (usize, usize), connectivity: Connectivity)
+    -> impl Iterator<Item = (usize, usize)>
+{
+    let offsets: &'static [(i64, i64)] = match connectivity {
+        Connectivity::Four => &FOUR_OFFSETS,
+        Connectivity::Eight => &EIGHT_OFFSETS,
+    };
+    offsets.iter().filter_map(move |(dh, dw)| {
+        let h = pos.0 as i64 + dh;
+        let w = pos.1 as i64 + dw;
+        if h < 0 || w < 0 || h as usize >= dim.0 || w as usize >= dim.1 {
+            return None;
+        }
+        Some((h as usize, w as usize))
+    })
+}
+
+/// Inclusive bounds of a set of cells, as (row, col) pairs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundingBox
+{
+    pub min: (usize, usize),
+    pub max: (usize, usize),
+}
+
+impl BoundingBox
+{
+    pub fn height(&self)
+        -> usize
+    {
+        self.max.0 - self.min.0 + 1
+    }
+
+    pub fn width(&self)
+        -> usize
+    {
+        self.max.1 - self.min.1 + 1
+    }
+
+    pub fn area(&self)
+        -> usize
+    {
+        self.height() * self.width()
+    }
+}
+
+/// Shape metrics of a single region
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegionShape
+{
+    pub area: usize,
+    pub perimeter: usize,
+    /// number of straight edges, holes included
+    pub sides: usize,
+    pub bounding_box: BoundingBox,
+    /// area divided by bounding box area, 1.0 for a filled rectangle
+    pub extent: f64,
+    /// enclosed pockets of cells not in the region
+    pub holes: usize,
+}
+
+/// Membership set of a region, sparse so cells far apart cost no more
+/// than cells next to each other
+struct RegionCells
+{
+    cells: HashSet<(i64, i64)>,
+    bounding_box: BoundingBox,
+}
+
+impl RegionCells
+{
+    fn new(cells: &[(usize, usize)])
+        -> Option<RegionCells>
+    {
+        let bounding_box = region_bounding_box(cells)?;
+        let cells = cells.iter()
+            .map(|&(h, w)| { (h as i64, w as i64) })
+            .collect();
+        Some(RegionCells { cells, bounding_box })
+    }
+
+    fn contains(&self, h: i64, w: i64)
+        -> bool
+    {
+        self.cells.contains(&(h, w))
+    }
+
+    fn cells(&self)
+        -> impl Iterator<Item = (i64, i64)> + '_
+    {
+        self.cells.iter().copied()
+    }
+
+    fn area(&self)
+        -> usize
+    {
+        self.cells.len()
+    }
+
+    fn perimeter(&self)
+        -> usize
+    {
+        self.cells().map(|(h, w)| {
+            [(h-1, w), (h+1, w), (h, w-1), (h, w+1)].iter()
+                .filter(|(h, w)| { !self.contains(*h, *w) })
+                .count()
+        }).sum()
+    }
+
+    /// A polygon has as many sides as corners, so count the corners of each cell
+    fn sides(&self)
+        -> usize
+    {
+        self.cells().map(|(h, w)| {
+            [(-1, -1), (-1, 1), (1, -1), (1, 1)].iter()
+                .filter(|(dh, dw)| {
+                    let vertical = self.contains(h + dh, w);
+                    let horizontal = self.contains(h, w + dw);
+                    let diagonal = self.contains(h + dh, w + dw);
+                    (!vertical && !horizontal)
+                        || (vertical && horizontal && !diagonal)
+                })
+                .count()
+        }).sum()
+    }
+
+    /// Number of 8-connected pieces the region falls apart in
+    fn components(&self)
+        -> i64
+    {
+        let mut seen = HashSet::new();
+        let mut components = 0;
+        for start in self.cells() {
+            if !seen.insert(start) {
+                continue;
+            }
+            components += 1;
+            let mut horizon = vec![start];
+            while let Some((h, w)) = horizon.pop() {
+                for dh in -1..=1 {
+                    for dw in -1..=1 {
+                        let adjecent = (h + dh, w + dw);
+                        if self.cells.contains(&adjecent) && seen.insert(adjecent) {
+                            horizon.push(adjecent);
+                        }
+                    }
+                }
+            }
+        }
+        components
+    }
+
+    /// The Euler number of the region is its 8-connected pieces minus its
+    /// 4-connected holes, and it can be counted from the 2x2 windows
+    /// touching the region alone, so the holes are never flooded
+    fn holes(&self)
+        -> usize
+    {
+        // every window touching a cell, by its top left corner
+        let windows: HashSet<(i64, i64)> = self.cells()
+            .flat_map(|(h, w)| { [(h-1, w-1), (h-1, w), (h, w-1), (h, w)] })
+            .collect();
+        let (mut single, mut triple, mut diagonal) = (0, 0, 0);
+        for (h, w) in windows {
+            let corners = [
+                self.contains(h, w), self.contains(h, w+1),
+                self.contains(h+1, w), self.contains(h+1, w+1),
+            ];
+            match corners.iter().filter(|x| { **x }).count() {
+                1 => single += 1,
+                3 => triple += 1,
+                2 if corners[0] == corners[3] => diagonal += 1,
+                _ => {},
+            }
+        }
+        let euler = (single - triple - 2 * diagonal) / 4;
+        (self.components() - euler) as usize
+    }
+}
+
+/// Number of distinct cells in the region
+pub fn region_area(cells: &[(usize, usize)])
+    -> usize
+{
+    RegionCells::new(cells).map_or(0, |region| { region.area() })
+}
+
+/// Number of cell edges bordering something outside the region, holes included
+pub fn region_perimeter(cells: &[(usize, usize)])
+    -> usize
+{
+    RegionCells::new(cells).map_or(0, |region| { region.perimeter() })
+}
+
+/// Number of straight sides of the region outline, holes included
+pub fn region_sides(cells: &[(usize, usize)])
+    -> usize
+{
+    RegionCells::new(cells).map_or(0, |region| { region.sides() })
+}
+
+/// Smallest box containing every cell, None for an empty region
+pub fn region_bounding_box(cells: &[(usize, usize)])
+    -> Option<BoundingBox>
+{
+    let first = *cells.first()?;
+    Some(cells.iter().fold(BoundingBox { min: first, max: first }, |acc, cell| {
+        BoundingBox {
+            min: (acc.min.0.min(cell.0), acc.min.1.min(cell.1)),
+            max: (acc.max.0.max(cell.0), acc.max.1.max(cell.1)),
+        }
+    }))
+}
+
+/// Number of pockets fully enclosed by the region
+/// Pockets are 4-connected, so a diagonal gap in the outline still encloses
+pub fn region_holes(cells: &[(usize, usize)])
+    -> usize
+{
+    RegionCells::new(cells).map_or(0, |region| { region.holes() })
+}
+
+/// All shape metrics of a region in one pass, None for an empty region
+pub fn region_shape(cells: &[(usize, usize)])
+    -> Option<RegionShape>
+{
+    let region = RegionCells::new(cells)?;
+    let area = region.area();
+    Some(RegionShape {
+        area,
+        perimeter: region.perimeter(),
+        sides: region.sides(),
+        bounding_box: region.bounding_box,
+        extent: area as f64 / region.bounding_box.area() as f64,
+        holes: region.holes(),
+    })
+}
+
+/// Group the cells of a label grid by label
+pub fn label_regions(labels: &Array2<usize>)
+    -> BTreeMap<usize, Vec<(usize, usize)>>
+{
+    let mut regions: BTreeMap<usize, Vec<(usize, usize)>> = BTreeMap::new();
+    for (index, label) in labels.indexed_iter() {
+        regions.entry(*label).or_default().push(index);
+    }
+    regions
+}
+
+/// Shape metrics for every label in the grid
+/// Cells sharing a label are one region, even when not connected
+pub fn label_shapes(labels: &Array2<usize>)
+    -> BTreeMap<usize, RegionShape>
+{
+    label_regions(labels).into_iter()
+        .filter_map(|(label, cells)| {
+            region_shape(&cells).map(|shape| { (label, shape) })
+        })
+        .collect()
+}
+
+/// How a cell compares to its neighbours to count as an extremum
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison
+{
+    /// every neighbour must be strictly higher (or lower)
+    Strict,
+    /// neighbours may be equal, so plateaus count
+    NonStrict,
+}
+
+/// Is pos at most as high as all its neighbours
+pub fn is_local_minimum<T: Ord>(grid: &Array2<T>, pos: (usize, usize)
+        , connectivity: Connectivity
+        , comparison: Comparison
+)
+    -> bool
+{
+    neighbours(grid.dim(), pos, connectivity).all(|n| {
+        match comparison {
+            Comparison::Strict => grid[n] > grid[pos],
+            Comparison::NonStrict => grid[n] >= grid[pos],
+        }
+    })
+}
+
+/// Is pos at least as high as all its neighbours
+pub fn is_local_maximum<T: Ord>(grid: &Array2<T>, pos: (usize, usize)
+        , connectivity: Connectivity
+        , comparison: Comparison
+)
+    -> bool
+{
+    neighbours(grid.dim(), pos, connectivity).all(|n| {
+        match comparison {
+            Comparison::Strict => grid[n] < grid[pos],
+            Comparison::NonStrict => grid[n] <= grid[pos],
+        }
+    })
+}
+
+/// All local minima in row major order
+pub fn local_minima<T: Ord>(grid: &Array2<T>
+        , connectivity: Connectivity
+        , comparison: Comparison
+)
+    -> Vec<(usize, usize)>
+{
+    grid.indexed_iter()
+        .map(|(index, _)| { index })
+        .filter(|index| { is_local_minimum(grid, *index, connectivity, comparison) })
+        .collect()
+}
+
+/// All local maxima in row major order
+pub fn local_maxima<T: Ord>(grid: &Array2<T>
+        , connectivity: Connectivity
+        , comparison: Comparison
+)
+    -> Vec<(usize, usize)>
+{
+    grid.indexed_iter()
+        .map(|(index, _)| { index })
+        .filter(|index| { is_local_maximum(grid, *index, connectivity, comparison) })
+        .collect()
+}
+
+/// Lowest neighbour strictly below pos, first one in neighbour order on ties
+pub fn steepest_descent<T: Ord>(grid: &Array2<T>, pos: (usize, usize)
+        , connectivity: Connectivity
+)
+    -> Option<(usize, usize)>
+{
+    neighbours(grid.dim(), pos, connectivity)
+        .filter(|n| { grid[*n] < grid[pos] })
+        .fold(None, |lowest: Option<(usize, usize)>, n| {
+            match lowest {
+                Some(l) if grid[l] <= grid[n] => Some(l),
+                _ => Some(n),
+            }
+        })
+}
+
+/// Next cell downhill of every cell, None for sinks
+/// Cells of a plateau without a way down flow across it to the nearest
+/// cell of the plateau that has one, a plateau without any drains into
+/// its first cell in row major order
+fn flow_map<T: Ord>(grid: &Array2<T>, connectivity: Connectivity)
+    -> Array2<Option<(usize, usize)>>
+{
+    let mut next: Array2<Option<(usize, usize)>> = Array2::from_shape_fn(grid.raw_dim(), |pos| {
+        steepest_descent(grid, pos, connectivity)
+    });
+    let mut seen = Array2::from_elem(grid.raw_dim(), false);
+
+    for (start, _) in grid.indexed_iter() {
+        if next[start].is_some() || seen[start] {
+            continue;
+        }
+        seen[start] = true;
+        let mut plateau = vec![start];
+        let mut i = 0;
+        while i < plateau.len() {
+            let pos = plateau[i];
+            i += 1;
+            for n in neighbours(grid.dim(), pos, connectivity) {
+                if !seen[n] && grid[n] == grid[pos] {
+                    seen[n] = true;
+                    plateau.push(n);
+                }
+            }
+        }
+        plateau.sort_unstable();
+
+        // breadth first from the ways down, each cell points one step closer
+        let mut horizon: VecDeque<(usize, usize)> = plateau.iter()
+            .filter(|x| { next[**x].is_some() })
+            .copied()
+            .collect();
+        if horizon.is_empty() {
+            for &pos in &plateau[1..] {
+                next[pos] = Some(plateau[0]);
+            }
+            continue;
+        }
+        while let Some(pos) = horizon.pop_front() {
+            for n in neighbours(grid.dim(), pos, connectivity) {
+                if next[n].is_none() && grid[n] == grid[pos] {
+                    next[n] = Some(pos);
+                    horizon.push_back(n);
+                }
+            }
+        }
+    }
+    next
+}
+
+/// Map every cell to the sink it drains into by following the steepest descent
+/// Plateaus are crossed to their way down, a plateau without one is a single
+/// sink, named by its first cell
+/// Sinks map to themselves, every path is only walked once
+pub fn drain_map<T: Ord>(grid: &Array2<T>, connectivity: Connectivity)
+    -> Array2<(usize, usize)>
+{
+    let next = flow_map(grid, connectivity);
+    let mut sinks: Array2<Option<(usize, usize)>> = Array2::from_elem(grid.raw_dim(), None);
+    let mut path = Vec::new();
+
+    for (start, _) in grid.indexed_iter() {
+        let mut pos = start;
+        let sink = loop {
+            if let Some(sink) = sinks[pos] {
+                break sink;
+            }
+            match next[pos] {
+                Some(next) => {
+                    path.push(pos);
+                    pos = next;
+                },
+                None => break pos,
+            }
+        };
+        sinks[pos] = Some(sink);
+        for visited in path.drain(..) {
+            sinks[visited] = Some(sink);
+        }
+    }
+
+    sinks.mapv(|x| { x.expect("every cell has a sink") })
+}
+
 #[cfg(test)]
 mod tests
 {
@@ -119,4 +579,167 @@ mod tests
             ],
         );
     }
+
+    #[test]
+    /// filled rectangle
+    fn test_0x0005()
+    {
+        let cells: Vec<(usize, usize)> = (2..4)
+            .flat_map(|h| { (1..4).map(move |w| { (h, w) }) })
+            .collect();
+        let shape = region_shape(&cells).unwrap();
+        assert_eq!(shape, RegionShape {
+            area: 6,
+            perimeter: 10,
+            sides: 4,
+            bounding_box: BoundingBox { min: (2,1), max: (3,3) },
+            extent: 1.0,
+            holes: 0,
+        });
+    }
+
+    #[test]
+    /// ring with a hole in the middle
+    fn test_0x0006()
+    {
+        let cells = vec![(0,0), (0,1), (0,2),
+                         (1,0),        (1,2),
+                         (2,0), (2,1), (2,2)];
+        assert_eq!(region_area(&cells), 8);
+        assert_eq!(region_perimeter(&cells), 16);
+        assert_eq!(region_sides(&cells), 8);
+        assert_eq!(region_holes(&cells), 1);
+        assert_eq!(region_shape(&cells).unwrap().extent, 8.0 / 9.0);
+        assert_eq!(region_shape(&[]), None);
+    }
+
+    #[test]
+    /// sides of concave region
+    fn test_0x0007()
+    {
+        let labels = arr2(&[
+            [1,1,1,1,1],
+            [1,2,2,2,2],
+            [1,1,1,1,1],
+            [1,3,3,3,3],
+            [1,1,1,1,1],
+        ]);
+        let shapes = label_shapes(&labels);
+        assert_eq!(shapes[&1].area, 17);
+        assert_eq!(shapes[&1].sides, 12);
+        assert_eq!(shapes[&1].holes, 0);
+        assert_eq!(shapes[&2].sides, 4);
+    }
+
+    #[test]
+    /// metrics of every label
+    fn test_0x0008()
+    {
+        let labels = arr2(&[
+            [0,0,0,0],
+            [1,1,2,3],
+            [1,1,2,2],
+            [4,4,4,2],
+        ]);
+        let shapes = label_shapes(&labels);
+        let metrics: Vec<(usize, usize, usize)> = shapes.values()
+            .map(|x| { (x.area, x.perimeter, x.sides) })
+            .collect();
+        assert_eq!(metrics, vec![(4,10,4), (4,8,4), (4,10,8), (1,4,4), (3,8,4)]);
+        assert_eq!(shapes[&2].bounding_box,
+            BoundingBox { min: (1,2), max: (3,3) });
+    }
+
+    #[test]
+    /// neighbours in the corner and middle
+    fn test_0x0009()
+    {
+        let n: Vec<_> = neighbours((5,5), (0,0), Connectivity::Four).collect();
+        assert_eq!(n, vec![(1,0), (0,1)]);
+        let n: Vec<_> = neighbours((5,5), (2,2), Connectivity::Eight).collect();
+        assert_eq!(n, find_sorounding(&Array2::zeros((5,5)), (2,2)));
+    }
+
+    #[test]
+    /// strict and non strict extrema
+    fn test_0x000a()
+    {
+        let grid = arr2(&[
+            [1,1,5],
+            [4,2,4],
+            [2,4,1],
+        ]);
+        assert_eq!(local_minima(&grid, Connectivity::Four, Comparison::Strict),
+            vec![(2,0), (2,2)]);
+        assert_eq!(local_minima(&grid, Connectivity::Four, Comparison::NonStrict),
+            vec![(0,0), (0,1), (2,0), (2,2)]);
+        assert_eq!(local_minima(&grid, Connectivity::Eight, Comparison::Strict),
+            vec![(2,2)]);
+        assert_eq!(local_maxima(&grid, Connectivity::Four, Comparison::Strict),
+            vec![(0,2), (1,0), (2,1)]);
+        assert_eq!(local_maxima(&grid, Connectivity::Eight, Comparison::Strict),
+            vec![(0,2)]);
+        assert_eq!(local_maxima(&grid, Connectivity::Eight, Comparison::NonStrict),
+            vec![(0,2), (1,0), (2,1)]);
+    }
+
+    #[test]
+    /// every cell drains to a sink
+    fn test_0x000b()
+    {
+        let grid = arr2(&[
+            [0,1,2,3],
+            [1,2,3,2],
+            [9,9,2,1],
+        ]);
+        assert_eq!(steepest_descent(&grid, (0,0), Connectivity::Four), None);
+        assert_eq!(steepest_descent(&grid, (1,1), Connectivity::Four), Some((0,1)));
+        let drains = drain_map(&grid, Connectivity::Four);
+        assert_eq!(drains, arr2(&[
+            [(0,0), (0,0), (0,0), (2,3)],
+            [(0,0), (0,0), (0,0), (2,3)],
+            [(0,0), (0,0), (2,3), (2,3)],
+        ]));
+    }
+
+    #[test]
+    /// sparse regions, cells far apart and holes closed diagonally
+    fn test_0x000c()
+    {
+        let far = 1 << 20;
+        let shape = region_shape(&[(0,0), (far,far)]).unwrap();
+        assert_eq!((shape.area, shape.perimeter, shape.sides, shape.holes), (2, 8, 8, 0));
+        assert_eq!(shape.bounding_box, BoundingBox { min: (0,0), max: (far,far) });
+
+        let diamond = |h: usize, w: usize| { vec![(h,w+1), (h+1,w), (h+1,w+2), (h+2,w+1)] };
+        assert_eq!(region_holes(&diamond(0, 0)), 1);
+        let two_diamonds = [diamond(0, 0), diamond(far, 3)].concat();
+        assert_eq!(region_holes(&two_diamonds), 2);
+    }
+
+    #[test]
+    /// plateaus drain across to their way down, or are one sink
+    fn test_0x000d()
+    {
+        let grid = arr2(&[
+            [9,9,9,9,9],
+            [9,1,5,5,9],
+            [9,9,9,9,9],
+        ]);
+        let drains = drain_map(&grid, Connectivity::Four);
+        assert_eq!(drains[(1,3)], (1,1));
+        assert_eq!(drains[(1,2)], (1,1));
+
+        let grid = arr2(&[
+            [3,2,2,2],
+            [3,3,2,3],
+            [4,4,4,4],
+        ]);
+        let drains = drain_map(&grid, Connectivity::Four);
+        assert_eq!(drains, arr2(&[
+            [(0,1), (0,1), (0,1), (0,1)],
+            [(0,1), (0,1), (0,1), (0,1)],
+            [(0,1), (0,1), (0,1), (0,1)],
+        ]));
+    }
 }
@@ -1,11 +1,25 @@
 pub mod threadpool;
+pub mod parallel;
 pub mod gridops;
+pub mod memo;
+pub mod bitops;
+pub mod solution;
+pub mod answers;
+pub mod bench;
 
 pub mod fileops {
-    use std::fs::File;
+    use std::fs::{self, File};
     use std::path::Path;
     use std::io::{self, BufRead};
 
+    /// Whole file as text, what Solution::parse takes
+    pub fn read_file<P>(path: P)
+        -> String
+    where P: AsRef<Path>
+    {
+        fs::read_to_string(path).expect("couldn't read file!")
+    }
+
     pub fn get_file_lines<P>(path: P)
         -> impl Iterator<Item = String>
     where P: AsRef<Path>
@@ -17,37 +31,3 @@ pub mod fileops {
             })
     }
 }
-
-pub mod bitops {
-
-    pub fn bitvec_to_num(bits: &[u8])
-        -> i32
-    {
-        bits.iter().fold(0, |acc, x| {
-            let res = (acc << 1) + *x as i32;
-            res
-        })
-    }
-
-    pub fn bitstr_to_vec(line: &str)
-        -> Vec<i32>
-    {
-        line.chars()
-            .map(|x| {
-                match x {
-                    '0' => 0,
-                    '1' => 1,
-                    _ => unreachable!(),
-                }
-            })
-            .collect()
-    }
-
-    pub fn bitstr_to_num(bitstr: &str)
-        -> i32
-    {
-        let vec: Vec<u8> = bitstr_to_vec(bitstr).iter()
-            .map(|x| { *x as u8 }).collect();
-        bitvec_to_num(&vec)
-    }
-}
@@ -0,0 +1,209 @@
+//! Caches for recursive solvers, safe to share between threads
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{
+    PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard,
+};
+
+/// Results of a function by its arguments
+/// Values are computed without holding the lock, so the function may recurse
+/// into the same memo, two threads missing the same key both compute it
+pub struct Memo<K, V>
+{
+    map: RwLock<HashMap<K, V>>,
+}
+
+impl<K, V> Default for Memo<K, V>
+{
+    fn default()
+        -> Memo<K, V>
+    {
+        Memo { map: RwLock::new(HashMap::new()) }
+    }
+}
+
+impl<K, V> Memo<K, V>
+{
+    pub fn new()
+        -> Memo<K, V>
+    {
+        Memo::default()
+    }
+
+    fn read(&self)
+        -> RwLockReadGuard<'_, HashMap<K, V>>
+    {
+        self.map.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self)
+        -> RwLockWriteGuard<'_, HashMap<K, V>>
+    {
+        self.map.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn len(&self)
+        -> usize
+    {
+        self.read().len()
+    }
+
+    pub fn is_empty(&self)
+        -> bool
+    {
+        self.read().is_empty()
+    }
+
+    /// Forget everything, needed when what the values depend on changes
+    pub fn clear(&self)
+    {
+        self.write().clear();
+    }
+}
+
+impl<K, V> Memo<K, V>
+where
+    K: Eq + Hash,
+    V: Clone,
+{
+    pub fn get(&self, key: &K)
+        -> Option<V>
+    {
+        self.read().get(key).cloned()
+    }
+
+    pub fn insert(&self, key: K, value: V)
+    {
+        self.write().insert(key, value);
+    }
+
+    /// Cached value of key, computed with f on a miss
+    pub fn get_or_insert_with<F>(&self, key: K, f: F)
+        -> V
+    where
+        F: FnOnce() -> V,
+    {
+        if let Some(value) = self.get(&key) {
+            return value;
+        }
+        let value = f();
+        // if another thread got there first keep its value, both are equal
+        self.write().entry(key).or_insert(value).clone()
+    }
+}
+
+/// Define a function that caches its results in a static Memo, keyed on a
+/// tuple of all arguments. The caller names the static so it can be
+/// cleared, arguments have to be Clone + Eq + Hash and the result Clone.
+///
+/// ```
+/// aoclib::memoize! {
+///     static FIB_CACHE;
+///     fn fib(n: u64) -> u64 {
+///         if n < 2 { n } else { fib(n - 1) + fib(n - 2) }
+///     }
+/// }
+///
+/// assert_eq!(fib(90), 2880067194370816120);
+/// FIB_CACHE.clear();
+/// ```
+#[macro_export]
+macro_rules! memoize {
+    (
+        $(#[$cache_attr:meta])*
+        $cache_vis:vis static $cache:ident;
+        $(#[$attr:meta])*
+        $vis:vis fn $name:ident($($arg:ident: $arg_ty:ty),* $(,)?) -> $ret:ty $body:block
+    ) => {
+        $(#[$cache_attr])*
+        $cache_vis static $cache: ::std::sync::LazyLock<$crate::memo::Memo<($($arg_ty,)*), $ret>> =
+            ::std::sync::LazyLock::new($crate::memo::Memo::new);
+
+        $(#[$attr])*
+        $vis fn $name($($arg: $arg_ty),*) -> $ret
+        {
+            let key = ($(::std::clone::Clone::clone(&$arg),)*);
+            $cache.get_or_insert_with(key, move | | { $body })
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use std::sync::atomic::{
+        AtomicUsize, Ordering,
+    };
+
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    crate::memoize! {
+        static PATHS_CACHE;
+        /// lattice paths through a width x height grid
+        fn paths(width: usize, height: usize) -> u64 {
+            CALLS.fetch_add(1, Ordering::SeqCst);
+            if width == 0 || height == 0 {
+                return 1;
+            }
+            paths(width - 1, height) + paths(width, height - 1)
+        }
+    }
+
+    #[test]
+    /// test get_or_insert_with only computes misses and clear forgets
+    fn test_0x001()
+    {
+        let memo: Memo<u32, String> = Memo::new();
+        assert_eq!(memo.get_or_insert_with(1, | | { "one".to_string() }), "one");
+        assert_eq!(memo.get_or_insert_with(1, | | { unreachable!() }), "one");
+        memo.insert(2, "two".to_string());
+        assert_eq!(memo.get(&2).as_deref(), Some("two"));
+        assert_eq!(memo.len(), 2);
+
+        memo.clear();
+        assert!(memo.is_empty());
+        assert_eq!(memo.get(&1), None);
+    }
+
+    #[test]
+    /// test memoized recursion computes every key once, also after clearing
+    fn test_0x002()
+    {
+        assert_eq!(paths(16, 16), 601080390);
+        // one call per distinct (width, height)
+        assert_eq!(CALLS.load(Ordering::SeqCst), PATHS_CACHE.len());
+        assert_eq!(PATHS_CACHE.len(), 17 * 17 - 1);
+
+        PATHS_CACHE.clear();
+        CALLS.store(0, Ordering::SeqCst);
+        assert_eq!(paths(2, 2), 6);
+        assert_eq!(CALLS.load(Ordering::SeqCst), 3 * 3 - 1);
+    }
+
+    #[test]
+    /// test a memo shared by threads gives the same results
+    fn test_0x003()
+    {
+        let memo: Memo<u64, u64> = Memo::new();
+        fn collatz(n: u64, memo: &Memo<u64, u64>)
+            -> u64
+        {
+            if n == 1 {
+                return 0;
+            }
+            let next = if n.is_multiple_of(2) { n / 2 } else { 3 * n + 1 };
+            memo.get_or_insert_with(n, | | { 1 + collatz(next, memo) })
+        }
+
+        let steps: Vec<u64> = std::thread::scope(|s| {
+            let handles: Vec<_> = (0..4)
+                .map(|_| { s.spawn(| | { (1..1000).map(|x| { collatz(x, &memo) }).sum() }) })
+                .collect();
+            handles.into_iter().map(|x| { x.join().unwrap() }).collect()
+        });
+        assert!(steps.iter().all(|&x| { x == steps[0] }));
+        assert_eq!(collatz(27, &memo), 111);
+    }
+}
@@ -0,0 +1,165 @@
+use super::threadpool::{JobHandle, ThreadPool};
+
+/// Data parallel operations over a slice, run as chunks on a ThreadPool
+/// Results always come back in input order
+pub struct Par<'a, T>
+{
+    pool: &'a ThreadPool,
+    items: &'a [T],
+    chunk_size: usize,
+}
+
+/// Entry point for Par, so any slice or Vec can do `lines.par(&pool)`
+pub trait ParallelSlice<T>
+{
+    fn par<'a>(&'a self, pool: &'a ThreadPool)
+        -> Par<'a, T>;
+}
+
+impl<T: Sync> ParallelSlice<T> for [T]
+{
+    fn par<'a>(&'a self, pool: &'a ThreadPool)
+        -> Par<'a, T>
+    {
+        Par::new(pool, self)
+    }
+}
+
+impl<'a, T: Sync> Par<'a, T>
+{
+    /// Defaults to about four chunks per worker, to even out uneven jobs
+    pub fn new(pool: &'a ThreadPool, items: &'a [T])
+        -> Par<'a, T>
+    {
+        let num_chunks = (pool.size() * 4).max(1);
+        let chunk_size = items.len().div_ceil(num_chunks).max(1);
+        Par { pool, items, chunk_size }
+    }
+
+    /// Number of items handed to each job
+    pub fn chunk_size(mut self, chunk_size: usize)
+        -> Par<'a, T>
+    {
+        assert!(chunk_size > 0, "chunk size must be positive");
+        self.chunk_size = chunk_size;
+        self
+    }
+
+    /// Apply f to every chunk, one result per chunk
+    /// Panics in the caller if any f panicked
+    /// A manual pool is stepped on the calling thread until every chunk is done
+    pub fn par_chunks<U, F>(&self, f: F)
+        -> Vec<U>
+    where
+        U: Send,
+        F: Fn(&'a [T]) -> U + Sync,
+    {
+        let f = &f;
+        self.pool.scope(|s| {
+            let handles: Vec<JobHandle<U>> = self.items.chunks(self.chunk_size)
+                .map(|chunk| { s.spawn(move | | { f(chunk) }) })
+                .collect();
+            handles.into_iter().map(|x| { self.pool.join_propagate(x) }).collect()
+        })
+    }
+
+    /// Apply f to every item
+    pub fn par_map<U, F>(&self, f: F)
+        -> Vec<U>
+    where
+        U: Send,
+        F: Fn(&'a T) -> U + Sync,
+    {
+        self.par_chunks(|chunk| {
+            chunk.iter().map(&f).collect::<Vec<U>>()
+        }).into_iter().flatten().collect()
+    }
+
+    /// Keep the items f returns true for
+    pub fn par_filter<F>(&self, f: F)
+        -> Vec<&'a T>
+    where
+        F: Fn(&T) -> bool + Sync,
+    {
+        self.par_chunks(|chunk| {
+            chunk.iter().filter(|x| { f(x) }).collect::<Vec<&T>>()
+        }).into_iter().flatten().collect()
+    }
+
+    /// Fold every chunk starting from identity, then combine the chunk results in order
+    pub fn par_reduce<U, F, R>(&self, identity: U, fold: F, combine: R)
+        -> U
+    where
+        U: Clone + Send + Sync,
+        F: Fn(U, &'a T) -> U + Sync,
+        R: Fn(U, U) -> U,
+    {
+        let identity_ref = &identity;
+        self.par_chunks(|chunk| {
+            chunk.iter().fold(identity_ref.clone(), &fold)
+        }).into_iter().fold(identity.clone(), combine)
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+    use crate::threadpool::{FakeClock, ThreadPoolBuilder};
+
+    #[test]
+    /// test map keeps input order for every chunk size
+    fn test_0x001()
+    {
+        let items: Vec<u64> = (0..1000).collect();
+        for size in [0, 1, 4] {
+            let pool = ThreadPool::new(size);
+            for chunk_size in [1, 7, 1000, 5000] {
+                let squares = items.par(&pool).chunk_size(chunk_size).par_map(|x| { x * x });
+                assert_eq!(squares, items.iter().map(|x| { x * x }).collect::<Vec<u64>>());
+            }
+        }
+    }
+
+    #[test]
+    /// test filter, reduce and chunks
+    fn test_0x002()
+    {
+        let pool = ThreadPool::new(3);
+        let lines = ["(]", "()", "[<>]", "{", "<>"];
+        let balanced = lines.par(&pool).chunk_size(2).par_filter(|x| { x.len() % 2 == 0 });
+        assert_eq!(balanced, vec![&"(]", &"()", &"[<>]", &"<>"]);
+
+        let concat = lines.par(&pool).chunk_size(2)
+            .par_reduce(String::new(), |acc, x| { acc + x }, |a, b| { a + &b });
+        assert_eq!(concat, "(]()[<>]{<>");
+
+        let lengths = lines.par(&pool).chunk_size(2).par_chunks(|x| { x.len() });
+        assert_eq!(lengths, vec![2, 2, 1]);
+
+        let empty: Vec<u8> = Vec::new();
+        assert_eq!(empty.par(&pool).par_reduce(0, |a, b| { a + b }, |a, b| { a + b }), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "bad item")]
+    /// test a panicking item reaches the caller
+    fn test_0x003()
+    {
+        let pool = ThreadPool::new(2);
+        let items = [1, 2, 3];
+        items.par(&pool).par_map(|x| { if *x == 2 { panic!("bad item") } x + 1 });
+    }
+
+    #[test]
+    /// test a manual pool is stepped instead of blocking forever
+    fn test_0x004()
+    {
+        let pool = ThreadPoolBuilder::new().manual(FakeClock::new()).build();
+        let items: Vec<u64> = (0..10).collect();
+        let squares = items.par(&pool).chunk_size(3).par_map(|x| { x * x });
+        assert_eq!(squares, items.iter().map(|x| { x * x }).collect::<Vec<u64>>());
+        assert_eq!(pool.submissions().len(), 4);
+        assert_eq!(pool.map(0..3, |x| { x + 1 }), vec![1, 2, 3]);
+    }
+}
@@ -0,0 +1,239 @@
+//! Common interface of the days, so one runner can find and run them all
+
+use std::any::Any;
+use std::fmt;
+use std::path::Path;
+
+use super::fileops;
+
+/// What a part returns, a number for most days
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer
+{
+    Number(i128),
+    Text(String),
+}
+
+impl fmt::Display for Answer
+{
+    fn fmt(&self, f: &mut fmt::Formatter)
+        -> fmt::Result
+    {
+        match self {
+            Answer::Number(x) => x.fmt(f),
+            Answer::Text(x) => x.fmt(f),
+        }
+    }
+}
+
+macro_rules! answer_from_int {
+    ($($int:ty),*) => {
+        $(
+            impl From<$int> for Answer
+            {
+                fn from(x: $int)
+                    -> Answer
+                {
+                    Answer::Number(x as i128)
+                }
+            }
+        )*
+    };
+}
+
+answer_from_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+impl From<String> for Answer
+{
+    fn from(x: String)
+        -> Answer
+    {
+        Answer::Text(x)
+    }
+}
+
+impl From<&str> for Answer
+{
+    fn from(x: &str)
+        -> Answer
+    {
+        Answer::Text(x.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Part
+{
+    One,
+    Two,
+}
+
+impl Part
+{
+    pub const BOTH: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl fmt::Display for Part
+{
+    fn fmt(&self, f: &mut fmt::Formatter)
+        -> fmt::Result
+    {
+        match self {
+            Part::One => "1".fmt(f),
+            Part::Two => "2".fmt(f),
+        }
+    }
+}
+
+/// One day's puzzle, the input is parsed once and shared by both parts
+pub trait Solution
+{
+    type Input: 'static;
+
+    /// Day of the month, the crate of day 9 is day9
+    fn day(&self)
+        -> u32;
+
+    /// Puzzle input as the text of the input file
+    fn parse(&self, input: &str)
+        -> Self::Input;
+
+    fn part1(&self, input: &Self::Input)
+        -> Answer;
+
+    fn part2(&self, input: &Self::Input)
+        -> Answer;
+}
+
+/// Solution with the input type erased, so days can share a list
+/// Implemented for every Solution
+pub trait DynSolution
+{
+    fn day(&self)
+        -> u32;
+
+    fn parse_dyn(&self, input: &str)
+        -> Box<dyn Any>;
+
+    /// Panics if input wasn't parsed by this solution
+    fn solve_dyn(&self, input: &dyn Any, part: Part)
+        -> Answer;
+}
+
+impl<S> DynSolution for S
+where
+    S: Solution,
+{
+    fn day(&self)
+        -> u32
+    {
+        Solution::day(self)
+    }
+
+    fn parse_dyn(&self, input: &str)
+        -> Box<dyn Any>
+    {
+        Box::new(self.parse(input))
+    }
+
+    fn solve_dyn(&self, input: &dyn Any, part: Part)
+        -> Answer
+    {
+        let input = input.downcast_ref::<S::Input>()
+            .expect("input parsed by another solution");
+        solve(self, input, part)
+    }
+}
+
+pub fn solve<S>(solution: &S, input: &S::Input, part: Part)
+    -> Answer
+where
+    S: Solution + ?Sized,
+{
+    match part {
+        Part::One => solution.part1(input),
+        Part::Two => solution.part2(input),
+    }
+}
+
+/// Parse the file at path and solve part
+pub fn solve_file<S, P>(solution: &S, path: P, part: Part)
+    -> Answer
+where
+    S: Solution,
+    P: AsRef<Path>,
+{
+    let input = solution.parse(&fileops::read_file(path));
+    solve(solution, &input, part)
+}
+
+/// What a day's own binary does, prints both answers for the input
+pub fn print_answers<S, P>(solution: &S, path: P)
+where
+    S: Solution,
+    P: AsRef<Path>,
+{
+    let input = solution.parse(&fileops::read_file(path));
+    for part in Part::BOTH {
+        println!("part{} {}", part, solve(solution, &input, part));
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    struct Sum;
+
+    impl Solution for Sum
+    {
+        type Input = Vec<i64>;
+
+        fn day(&self)
+            -> u32
+        {
+            0
+        }
+
+        fn parse(&self, input: &str)
+            -> Vec<i64>
+        {
+            input.lines().map(|x| { x.parse().unwrap() }).collect()
+        }
+
+        fn part1(&self, input: &Vec<i64>)
+            -> Answer
+        {
+            input.iter().sum::<i64>().into()
+        }
+
+        fn part2(&self, input: &Vec<i64>)
+            -> Answer
+        {
+            format!("{} numbers", input.len()).into()
+        }
+    }
+
+    #[test]
+    /// test answers compare and print the same whatever integer they came from
+    fn test_0x001()
+    {
+        assert_eq!(Answer::from(42u8), Answer::from(42usize));
+        assert_eq!(Answer::from(-1i32), Answer::Number(-1));
+        assert_eq!(Answer::from(u64::MAX).to_string(), u64::MAX.to_string());
+        assert_eq!(Answer::from("abc").to_string(), "abc");
+        assert_ne!(Answer::from("1"), Answer::from(1));
+    }
+
+    #[test]
+    /// test solving through the erased solution
+    fn test_0x002()
+    {
+        let solution: &dyn DynSolution = &Sum;
+        let input = solution.parse_dyn("1\n2\n-4\n");
+        assert_eq!(solution.solve_dyn(input.as_ref(), Part::One), Answer::from(-1));
+        assert_eq!(solution.solve_dyn(input.as_ref(), Part::Two).to_string(), "3 numbers");
+        assert_eq!(solve(&Sum, &Sum.parse("5"), Part::One), Answer::from(5));
+    }
+}
@@ -1,96 +1,1153 @@
+mod graph;
+mod manual;
+#[cfg(feature = "stats")]
+mod stats;
+pub use graph::{
+    TaskGraph, TaskId,
+};
+pub use manual::{
+    FakeClock, Submission,
+};
+#[cfg(feature = "stats")]
+pub use stats::{
+    Histogram, PoolStats, WorkerStats,
+};
+
+use crossbeam_channel::{
+    Receiver, Select, Sender, TryRecvError, TrySendError,
+};
+use crossbeam_utils::CachePadded;
+use manual::ManualQueue;
+use std::any::Any;
+use std::env;
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem;
+use std::panic::{
+    self, AssertUnwindSafe,
+};
 use std::thread;
 use std::sync::{
-    mpsc, Arc, Mutex,
+    mpsc, Arc, Condvar, Mutex, OnceLock, PoisonError,
+};
+use std::sync::atomic::{
+    AtomicBool, AtomicUsize, Ordering,
+};
+use std::time::{
+    Duration, Instant,
 };
 
-type Job = Box<dyn FnOnce() + Send + 'static>;
+/// A type erased job as it sits in the queue
+pub type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// Why try_execute could not queue a job, the job is handed back
+pub enum TryExecuteError
+{
+    /// the bounded queue has no room right now
+    Full(Job),
+    /// every worker is gone
+    Disconnected(Job),
+}
+
+impl TryExecuteError
+{
+    /// Take back the job, to run it elsewhere or try again later
+    pub fn into_job(self)
+        -> Job
+    {
+        match self {
+            TryExecuteError::Full(job) | TryExecuteError::Disconnected(job) => job,
+        }
+    }
+}
+
+impl fmt::Debug for TryExecuteError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>)
+        -> fmt::Result
+    {
+        match self {
+            TryExecuteError::Full(_) => write!(f, "Full(..)"),
+            TryExecuteError::Disconnected(_) => write!(f, "Disconnected(..)"),
+        }
+    }
+}
+
+impl fmt::Display for TryExecuteError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>)
+        -> fmt::Result
+    {
+        match self {
+            TryExecuteError::Full(_) => write!(f, "job queue is full"),
+            TryExecuteError::Disconnected(_) => write!(f, "all workers are gone"),
+        }
+    }
+}
+
+impl std::error::Error for TryExecuteError {}
 
-enum Message
+/// Why a job did not produce a result
+#[derive(Debug)]
+pub enum JobError
 {
-    NewJob(Job),
-    Terminate,
+    /// the job panicked, carries the panic payload
+    Panicked(Box<dyn Any + Send + 'static>),
+    /// the job was dropped without running
+    Cancelled,
+}
+
+impl fmt::Display for JobError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>)
+        -> fmt::Result
+    {
+        match self {
+            JobError::Panicked(payload) => {
+                if let Some(message) = payload.downcast_ref::<&str>() {
+                    write!(f, "job panicked: {}", message)
+                } else if let Some(message) = payload.downcast_ref::<String>() {
+                    write!(f, "job panicked: {}", message)
+                } else {
+                    write!(f, "job panicked")
+                }
+            },
+            JobError::Cancelled => write!(f, "job was cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for JobError {}
+
+/// Handle to the result of a job started with ThreadPool::submit
+pub struct JobHandle<T>
+{
+    reciever: mpsc::Receiver<thread::Result<T>>,
+}
+
+impl<T> JobHandle<T>
+{
+    /// Block until the job is done and take its result
+    pub fn join(self)
+        -> Result<T, JobError>
+    {
+        match self.reciever.recv() {
+            Ok(result) => result.map_err(JobError::Panicked),
+            Err(_) => Err(JobError::Cancelled),
+        }
+    }
+
+    /// Block until the job is done and take its result,
+    /// a panic in the job is resumed in the caller
+    pub(crate) fn join_propagate(self)
+        -> T
+    {
+        propagate(self.join())
+    }
+
+    /// Take the result if the job is done, without blocking
+    pub fn try_join(&self)
+        -> Option<Result<T, JobError>>
+    {
+        match self.reciever.try_recv() {
+            Ok(result) => Some(result.map_err(JobError::Panicked)),
+            Err(mpsc::TryRecvError::Empty) => None,
+            Err(mpsc::TryRecvError::Disconnected) => Some(Err(JobError::Cancelled)),
+        }
+    }
+}
+
+/// The result of a job, a panic in the job is resumed in the caller
+fn propagate<T>(result: Result<T, JobError>)
+    -> T
+{
+    match result {
+        Ok(result) => result,
+        Err(JobError::Panicked(payload)) => panic::resume_unwind(payload),
+        Err(error) => panic!("{}", error),
+    }
+}
+
+/// Wrap f in a job that reports its result or panic to the returned handle
+fn job_with_handle<F, T>(f: F)
+    -> (impl FnOnce() + Send, JobHandle<T>)
+where
+    F: FnOnce() -> T + Send,
+    T: Send,
+{
+    let (transmitter, reciever) = mpsc::channel();
+    let job = move | | {
+        let result = panic::catch_unwind(AssertUnwindSafe(f));
+        // nobody to report to if the handle is gone
+        let _ = transmitter.send(result);
+    };
+    (job, JobHandle { reciever })
+}
+
+/// Order in which queued jobs are taken, all High jobs before any Normal
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority
+{
+    /// for child tasks that should finish before new work starts
+    High,
+    #[default]
+    Normal,
+    Low,
+}
+
+const NUM_PRIORITIES: usize = 3;
+
+/// A job as it travels through the queue
+struct Task
+{
+    job: Job,
+    #[cfg(feature = "stats")]
+    queued_at: Instant,
+}
+
+impl Task
+{
+    fn new(job: Job)
+        -> Task
+    {
+        Task {
+            job,
+            #[cfg(feature = "stats")]
+            queued_at: Instant::now(),
+        }
+    }
+}
+
+/// Shared flag that long running jobs can poll to stop early
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken
+{
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken
+{
+    pub fn new()
+        -> CancellationToken
+    {
+        CancellationToken::default()
+    }
+
+    /// Every clone of this token sees the cancellation
+    pub fn cancel(&self)
+    {
+        self.cancelled.store(true, Ordering::Release);
+    }
+
+    pub fn is_cancelled(&self)
+        -> bool
+    {
+        self.cancelled.load(Ordering::Acquire)
+    }
+}
+
+/// State shared between the pool and its workers
+struct Shared
+{
+    /// one queue per priority, multi consumer so workers take jobs
+    /// without a lock, workers stop once they are empty and the pool
+    /// dropped its senders
+    recievers: Vec<Receiver<Task>>,
+    /// jobs queued with a priority other than Normal, polling an empty queue
+    /// costs more than running a no-op job, so workers skip those queues
+    /// while this is zero
+    prioritized: CachePadded<AtomicUsize>,
+    /// the queues have no capacity, a job is only handed to a worker
+    /// waiting in a receive
+    rendezvous: bool,
+    /// jobs handed to the queue, only producers write it
+    submitted: CachePadded<AtomicUsize>,
+    /// jobs each worker is done with, only that worker writes it
+    finished: Vec<CachePadded<AtomicUsize>>,
+    /// threads in wait_idle, workers only notify when there are any
+    waiters: AtomicUsize,
+    idle_lock: Mutex<()>,
+    idle: Condvar,
+    /// set when pending jobs should be dropped instead of run
+    discarding: AtomicBool,
+    /// one per worker, so workers never contend on them
+    #[cfg(feature = "stats")]
+    stats: Vec<Mutex<WorkerStats>>,
+    #[cfg(feature = "stats")]
+    print_stats: bool,
+    thread_name: String,
+    stack_size: Option<usize>,
+    /// replacements for workers that died, joined on drop
+    respawned: Mutex<Vec<Worker>>,
 }
 
 struct Worker
 {
-    id: usize,
-    //should join somewhere
     thread: Option<thread::JoinHandle<()>>,
 }
 
+/// Lives on the worker thread and replaces the worker if the thread unwinds
+struct Sentinel
+{
+    id: usize,
+    shared: Arc<Shared>,
+}
+
+impl Drop for Sentinel
+{
+    fn drop(&mut self) {
+        if !thread::panicking() {
+            return;
+        }
+        // if we can't spawn, the pool just runs with one worker less
+        if let Ok(worker) = Worker::spawn(self.id, Arc::clone(&self.shared)) {
+            self.shared.respawned.lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(worker);
+        }
+    }
+}
+
+impl Shared
+{
+    /// Highest priority task, blocks while every queue is empty
+    /// None once the queues are empty and the pool is shut down
+    fn next_task(&self)
+        -> Option<Task>
+    {
+        loop {
+            let mut polled = 0;
+            let mut disconnected = 0;
+            let skip = self.prioritized.load(Ordering::SeqCst) == 0;
+            for (priority, reciever) in self.recievers.iter().enumerate() {
+                let normal = priority == Priority::Normal as usize;
+                if skip && !normal {
+                    continue;
+                }
+                polled += 1;
+                match reciever.try_recv() {
+                    Ok(task) => {
+                        if !normal {
+                            self.prioritized.fetch_sub(1, Ordering::SeqCst);
+                        }
+                        return Some(task);
+                    },
+                    Err(TryRecvError::Empty) => {},
+                    Err(TryRecvError::Disconnected) => disconnected += 1,
+                }
+            }
+            // ran dry, the pool may be idle now
+            self.notify_idle();
+            // the senders are dropped together, any disconnected queue means all are
+            if disconnected == polled {
+                return None;
+            }
+
+            let mut select = Select::new();
+            for reciever in &self.recievers {
+                select.recv(reciever);
+            }
+            if !self.rendezvous {
+                // waking up to poll again is cheaper than a blocking receive,
+                // and lets the next round take the highest priority job
+                select.ready();
+                continue;
+            }
+            // a zero capacity queue only hands over a job to a worker waiting
+            // in a real receive, with every queue empty the first job to
+            // arrive is the one to take anyway
+            let operation = select.select();
+            let priority = operation.index();
+            // a disconnected queue is noticed by the next round of the loop
+            if let Ok(task) = operation.recv(&self.recievers[priority]) {
+                if priority != Priority::Normal as usize {
+                    self.prioritized.fetch_sub(1, Ordering::SeqCst);
+                }
+                return Some(task);
+            }
+        }
+    }
+
+    /// Every job submitted so far is finished
+    fn is_idle(&self)
+        -> bool
+    {
+        // finished only grows, so reading submitted first can't overshoot
+        // submitted only shrinks again for a job that could not be queued,
+        // which at worst makes a waiter wait for the next worker to run dry
+        let submitted = self.submitted.load(Ordering::SeqCst);
+        let finished: usize = self.finished.iter()
+            .map(|x| { x.load(Ordering::SeqCst) })
+            .sum();
+        finished >= submitted
+    }
+
+    /// Called by a worker that found the queue empty
+    fn notify_idle(&self)
+    {
+        // pairs with the waiter counting itself before checking is_idle
+        if self.waiters.load(Ordering::SeqCst) > 0 {
+            let _lock = self.idle_lock.lock().unwrap_or_else(PoisonError::into_inner);
+            self.idle.notify_all();
+        }
+    }
+
+    /// Wait until no job is queued or running, false if the deadline passed first
+    fn wait_idle(&self, deadline: Option<Instant>)
+        -> bool
+    {
+        let mut lock = self.idle_lock.lock().unwrap_or_else(PoisonError::into_inner);
+        self.waiters.fetch_add(1, Ordering::SeqCst);
+        let mut idle = true;
+        while !self.is_idle() {
+            match deadline {
+                None => {
+                    lock = self.idle.wait(lock).unwrap_or_else(PoisonError::into_inner);
+                },
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        idle = false;
+                        break;
+                    }
+                    lock = self.idle.wait_timeout(lock, deadline - now)
+                        .unwrap_or_else(PoisonError::into_inner).0;
+                },
+            }
+        }
+        self.waiters.fetch_sub(1, Ordering::SeqCst);
+        idle
+    }
+}
+
+/// Counts a taken job as finished however the worker leaves it
+struct FinishGuard<'a>(&'a AtomicUsize);
+
+impl Drop for FinishGuard<'_>
+{
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
 impl Worker
 {
-    fn new(id: usize, reciever: Arc<Mutex<mpsc::Receiver<Message>>>)
+    fn new(id: usize, shared: Arc<Shared>)
         -> Worker
     {
-        let thread = thread::Builder::new()
-            .name(format!("threadpool worker {}", id))
-            .spawn(move || loop {
-                let message = reciever.lock().expect("poisoned reciever")
-                    .recv().expect("no dispatcher");
-                match message {
-                    Message::NewJob(job) => job(),
-                    Message::Terminate => break,
-                }
+        Worker::spawn(id, shared).expect("can't spawn worker thread")
+    }
 
-        }).unwrap();
-        Worker { id, thread: Some(thread) }
+    fn spawn(id: usize, shared: Arc<Shared>)
+        -> std::io::Result<Worker>
+    {
+        let mut builder = thread::Builder::new()
+            .name(format!("{} {}", shared.thread_name, id));
+        if let Some(stack_size) = shared.stack_size {
+            builder = builder.stack_size(stack_size);
+        }
+        let thread = builder
+            .spawn(move || {
+                let sentinel = Sentinel { id, shared };
+                let shared = &sentinel.shared;
+                loop {
+                    #[cfg(feature = "stats")]
+                    let idle_since = Instant::now();
+                    let task = match shared.next_task() {
+                        Some(task) => task,
+                        None => break,
+                    };
+                    let _finish = FinishGuard(&shared.finished[id]);
+                    if shared.discarding.load(Ordering::Acquire) {
+                        continue;
+                    }
+                    #[cfg(feature = "stats")]
+                    let started = Instant::now();
+                    run_job(task.job);
+                    #[cfg(feature = "stats")]
+                    shared.stats[id].lock().unwrap_or_else(PoisonError::into_inner)
+                        .record(idle_since, task.queued_at, started);
+                }
+            })?;
+        Ok(Worker { thread: Some(thread) })
     }
 }
 
+/// Run a job, a panic is reported by the panic hook but goes no further
+fn run_job(job: Job)
+{
+    // the payload is dropped here, if that panics the sentinel takes over
+    let _ = panic::catch_unwind(AssertUnwindSafe(job));
+}
 
-pub struct ThreadPool
+
+/// Configures a ThreadPool before any worker is started
+#[derive(Debug, Clone)]
+pub struct ThreadPoolBuilder
 {
-    workers: Vec<Worker>,
-    dispatcher: mpsc::Sender<Message>,
+    num_threads: Option<usize>,
+    stack_size: Option<usize>,
+    thread_name: String,
+    queue_capacity: Option<usize>,
+    manual: Option<FakeClock>,
+    #[cfg(feature = "stats")]
+    print_stats: bool,
 }
 
-impl ThreadPool
+impl Default for ThreadPoolBuilder
 {
-    pub fn new(size: usize)
+    fn default()
+        -> ThreadPoolBuilder
+    {
+        ThreadPoolBuilder {
+            num_threads: None,
+            stack_size: None,
+            thread_name: "threadpool worker".to_string(),
+            queue_capacity: None,
+            manual: None,
+            #[cfg(feature = "stats")]
+            print_stats: false,
+        }
+    }
+}
+
+impl ThreadPoolBuilder
+{
+    pub fn new()
+        -> ThreadPoolBuilder
+    {
+        ThreadPoolBuilder::default()
+    }
+
+    /// Number of workers, 0 runs every job inline
+    /// Without this the AOC_THREADS env var is used, then the available parallelism
+    pub fn num_threads(mut self, num_threads: usize)
+        -> ThreadPoolBuilder
+    {
+        self.num_threads = Some(num_threads);
+        self
+    }
+
+    /// Stack size of each worker in bytes, deep recursion in jobs needs more
+    pub fn stack_size(mut self, stack_size: usize)
+        -> ThreadPoolBuilder
+    {
+        self.stack_size = Some(stack_size);
+        self
+    }
+
+    /// Workers are named "<prefix> <id>"
+    pub fn thread_name(mut self, prefix: &str)
+        -> ThreadPoolBuilder
+    {
+        self.thread_name = prefix.to_string();
+        self
+    }
+
+    /// Jobs that may wait in the queue before execute blocks, unbounded by default
+    /// Every priority level has its own queue of this size
+    /// With 0 every execute waits for a worker to take the job
+    pub fn queue_capacity(mut self, queue_capacity: usize)
+        -> ThreadPoolBuilder
+    {
+        self.queue_capacity = Some(queue_capacity);
+        self
+    }
+
+    /// Start no workers, jobs stay queued until ThreadPool::step runs them
+    /// on the calling thread, so tests don't depend on timing
+    /// Deadlines like shutdown_timeout are measured on clock
+    pub fn manual(mut self, clock: FakeClock)
+        -> ThreadPoolBuilder
+    {
+        self.manual = Some(clock);
+        self
+    }
+
+    /// Print ThreadPool::stats to stderr once the pool is shut down
+    #[cfg(feature = "stats")]
+    pub fn print_stats_on_drop(mut self, print_stats: bool)
+        -> ThreadPoolBuilder
+    {
+        self.print_stats = print_stats;
+        self
+    }
+
+    /// Panics if AOC_THREADS is set but is not a number
+    pub fn build(self)
         -> ThreadPool
     {
-        let (dispatcher, reciever) = mpsc::channel();
+        let size = match self.manual {
+            Some(_) => 0,
+            None => self.num_threads.unwrap_or_else(| | {
+                default_num_threads(env::var("AOC_THREADS").ok().as_deref())
+            }),
+        };
+        let (dispatcher, recievers) = (0..NUM_PRIORITIES)
+            .map(|_| {
+                match self.queue_capacity {
+                    None => crossbeam_channel::unbounded(),
+                    Some(capacity) => crossbeam_channel::bounded(capacity),
+                }
+            })
+            .unzip();
         let mut workers = Vec::with_capacity(size);
-        let reciever = Arc::new(Mutex::new(reciever));
+        let shared = Arc::new(Shared {
+            recievers,
+            prioritized: CachePadded::new(AtomicUsize::new(0)),
+            rendezvous: self.queue_capacity == Some(0),
+            submitted: CachePadded::new(AtomicUsize::new(0)),
+            finished: (0..size).map(|_| { CachePadded::new(AtomicUsize::new(0)) }).collect(),
+            waiters: AtomicUsize::new(0),
+            idle_lock: Mutex::new(()),
+            idle: Condvar::new(),
+            discarding: AtomicBool::new(false),
+            #[cfg(feature = "stats")]
+            stats: (0..size).map(|_| { Mutex::new(WorkerStats::default()) }).collect(),
+            #[cfg(feature = "stats")]
+            print_stats: self.print_stats,
+            thread_name: self.thread_name,
+            stack_size: self.stack_size,
+            respawned: Mutex::new(Vec::new()),
+        });
 
         for id in 0..size {
-            workers.push(Worker::new(id, Arc::clone(&reciever)));
+            workers.push(Worker::new(id, Arc::clone(&shared)));
         }
 
         ThreadPool {
-            workers, dispatcher
+            workers,
+            dispatcher: Some(dispatcher),
+            shared,
+            token: CancellationToken::new(),
+            manual: self.manual.map(ManualQueue::new),
         }
     }
+}
+
+/// The value of AOC_THREADS if set, otherwise whatever the machine offers
+fn default_num_threads(aoc_threads: Option<&str>)
+    -> usize
+{
+    if let Some(threads) = aoc_threads {
+        return threads.trim().parse()
+            .unwrap_or_else(|_| panic!("AOC_THREADS is not a number: {:?}", threads));
+    }
+    thread::available_parallelism().map_or(1, |x| { x.get() })
+}
+
+pub struct ThreadPool
+{
+    workers: Vec<Worker>,
+    /// None once the pool is shut down
+    dispatcher: Option<Vec<Sender<Task>>>,
+    shared: Arc<Shared>,
+    token: CancellationToken,
+    /// Some if built with ThreadPoolBuilder::manual, jobs go here instead
+    manual: Option<ManualQueue>,
+}
 
+impl ThreadPool
+{
+    /// Pool with exactly size workers, see ThreadPoolBuilder for more options
+    pub fn new(size: usize)
+        -> ThreadPool
+    {
+        ThreadPoolBuilder::new().num_threads(size).build()
+    }
+
+    /// Pool shared by everything that has no pool of its own, built on
+    /// first use with the defaults of ThreadPoolBuilder and never shut down
+    /// so code called over and over doesn't start threads each time
+    pub fn shared()
+        -> &'static ThreadPool
+    {
+        static SHARED: OnceLock<ThreadPool> = OnceLock::new();
+        SHARED.get_or_init(| | { ThreadPoolBuilder::new().build() })
+    }
+
+    /// Number of worker threads, 0 means jobs run inline or by step
+    pub fn size(&self)
+        -> usize
+    {
+        self.workers.len()
+    }
+
+    /// Run f on the pool, a panic in f is contained to the job
+    /// Blocks while a bounded queue is full
     pub fn execute<F,>(&self, f: F )
-        -> ()
     where
-        F: FnOnce() -> () + Send + 'static,
+        F: FnOnce() + Send + 'static,
     {
-        if self.workers.len() == 0 {
-            f();
+        self.dispatch(Box::new(f), Priority::Normal);
+    }
+
+    /// Like execute, queued jobs of higher priority are taken first
+    pub fn execute_with_priority<F,>(&self, priority: Priority, f: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.dispatch(Box::new(f), priority);
+    }
+
+    fn dispatch(&self, job: Job, priority: Priority)
+    {
+        if let Some(manual) = &self.manual {
+            manual.push(job, priority);
             return;
         }
+        if self.workers.is_empty() {
+            run_job(job);
+            return;
+        }
+
+        // counted before sending, so a worker taking it never sees zero,
+        // and a job queued by a running job is submitted before that one
+        // finishes, so is_idle can't see both done while the child still runs
+        if priority != Priority::Normal {
+            self.shared.prioritized.fetch_add(1, Ordering::SeqCst);
+        }
+        self.shared.submitted.fetch_add(1, Ordering::SeqCst);
+        if self.sender(priority).send(Task::new(job)).is_err() {
+            self.unsubmit(priority);
+            panic!("all workers are gone");
+        }
+    }
+
+    /// Take back the counts of a job that never made it into the queue
+    fn unsubmit(&self, priority: Priority)
+    {
+        self.shared.submitted.fetch_sub(1, Ordering::SeqCst);
+        if priority != Priority::Normal {
+            self.shared.prioritized.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    fn sender(&self, priority: Priority)
+        -> &Sender<Task>
+    {
+        &self.dispatcher.as_ref().expect("pool is shut down")[priority as usize]
+    }
+
+    /// Like execute, but hands the job back instead of blocking on a full queue
+    pub fn try_execute<F,>(&self, f: F)
+        -> Result<(), TryExecuteError>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.try_dispatch(Box::new(f), Priority::Normal)
+    }
+
+    fn try_dispatch(&self, job: Job, priority: Priority)
+        -> Result<(), TryExecuteError>
+    {
+        if self.manual.is_some() || self.workers.is_empty() {
+            self.dispatch(job, priority);
+            return Ok(());
+        }
+
+        // counted before sending for the same reasons as in dispatch
+        if priority != Priority::Normal {
+            self.shared.prioritized.fetch_add(1, Ordering::SeqCst);
+        }
+        self.shared.submitted.fetch_add(1, Ordering::SeqCst);
+        match self.sender(priority).try_send(Task::new(job)) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(task)) => {
+                self.unsubmit(priority);
+                Err(TryExecuteError::Full(task.job))
+            },
+            Err(TrySendError::Disconnected(task)) => {
+                self.unsubmit(priority);
+                Err(TryExecuteError::Disconnected(task.job))
+            },
+        }
+    }
 
-        self.dispatcher.send(Message::NewJob(Box::new(f))).unwrap();
+    /// Like execute, but the return value of f can be joined on through the handle
+    pub fn submit<F, T>(&self, f: F)
+        -> JobHandle<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        self.submit_with_priority(Priority::Normal, f)
+    }
+
+    /// Like submit, queued jobs of higher priority are taken first
+    pub fn submit_with_priority<F, T>(&self, priority: Priority, f: F)
+        -> JobHandle<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let (job, handle) = job_with_handle(f);
+        self.execute_with_priority(priority, job);
+        handle
+    }
+
+    /// Scatter f over all items and gather the results in item order
+    /// Panics with the original payload if any of the jobs panicked
+    pub fn map<I, F, T>(&self, items: I, f: F)
+        -> Vec<T>
+    where
+        I: IntoIterator,
+        I::Item: Send + 'static,
+        F: Fn(I::Item) -> T + Send + Sync + 'static,
+        T: Send + 'static,
+    {
+        let f = Arc::new(f);
+        let handles: Vec<JobHandle<T>> = items.into_iter()
+            .map(|item| {
+                let f = Arc::clone(&f);
+                self.submit(move | | { f(item) })
+            })
+            .collect();
+        handles.into_iter().map(|x| { self.join_propagate(x) }).collect()
+    }
+
+    /// JobHandle::join_propagate for a job of this pool, a manual pool
+    /// runs its queued jobs meanwhile as nobody else would
+    pub(crate) fn join_propagate<T>(&self, handle: JobHandle<T>)
+        -> T
+    {
+        if self.manual.is_none() {
+            return handle.join_propagate();
+        }
+        loop {
+            if let Some(result) = handle.try_join() {
+                return propagate(result);
+            }
+            // with nothing left to step the job was dropped, so join won't block
+            if self.step().is_none() {
+                return handle.join_propagate();
+            }
+        }
     }
 }
 
-impl Drop for ThreadPool
+impl ThreadPool
+{
+    /// Run f with a scope that can spawn jobs borrowing from the caller's stack
+    /// Returns once f and every job spawned in the scope has finished
+    /// Don't call from inside a job, waiting would occupy the worker
+    pub fn scope<'env, F, T>(&'env self, f: F)
+        -> T
+    where
+        F: for<'scope> FnOnce(&'scope Scope<'scope, 'env>) -> T,
+    {
+        let scope = Scope {
+            pool: self,
+            state: Arc::new(ScopeState {
+                pending: Mutex::new(0),
+                done: Condvar::new(),
+            }),
+            scope: PhantomData,
+            env: PhantomData,
+        };
+        let result = panic::catch_unwind(AssertUnwindSafe(| | { f(&scope) }));
+
+        // a manual pool has nobody else to run the jobs
+        if self.manual.is_some() {
+            while *scope.state.pending.lock().unwrap_or_else(PoisonError::into_inner) > 0 {
+                self.step();
+            }
+        }
+
+        // jobs borrow from the stack, so wait for them even if f panicked
+        let mut pending = scope.state.pending.lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        while *pending > 0 {
+            pending = scope.state.done.wait(pending)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+
+        match result {
+            Ok(result) => result,
+            Err(payload) => panic::resume_unwind(payload),
+        }
+    }
+}
+
+/// Spawns jobs that may borrow anything outliving the call to ThreadPool::scope
+pub struct Scope<'scope, 'env: 'scope>
+{
+    pool: &'env ThreadPool,
+    state: Arc<ScopeState>,
+    scope: PhantomData<&'scope mut &'scope ()>,
+    env: PhantomData<&'env mut &'env ()>,
+}
+
+struct ScopeState
+{
+    pending: Mutex<usize>,
+    done: Condvar,
+}
+
+/// A job of a scope, counted as pending until it is run or dropped
+struct ScopedJob
+{
+    job: Option<Job>,
+    state: Arc<ScopeState>,
+}
+
+impl ScopedJob
+{
+    fn run(mut self)
+    {
+        if let Some(job) = self.job.take() {
+            job();
+        }
+    }
+}
+
+impl Drop for ScopedJob
 {
     fn drop(&mut self) {
+        // whatever the job borrowed must be gone before the scope can end
+        drop(self.job.take());
+        let mut pending = self.state.pending.lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        *pending -= 1;
+        if *pending == 0 {
+            self.state.done.notify_all();
+        }
+    }
+}
+
+impl<'scope, 'env> Scope<'scope, 'env>
+{
+    /// Run f on the pool, its result or panic is reported through the handle
+    pub fn spawn<F, T>(&'scope self, f: F)
+        -> JobHandle<T>
+    where
+        F: FnOnce() -> T + Send + 'scope,
+        T: Send + 'scope,
+    {
+        self.spawn_with_priority(Priority::Normal, f)
+    }
+
+    /// Like spawn, queued jobs of higher priority are taken first
+    pub fn spawn_with_priority<F, T>(&'scope self, priority: Priority, f: F)
+        -> JobHandle<T>
+    where
+        F: FnOnce() -> T + Send + 'scope,
+        T: Send + 'scope,
+    {
+        let (job, handle) = self.scoped_job(f);
+        self.pool.dispatch(job, priority);
+        handle
+    }
 
-        for _ in &mut self.workers {
-            self.dispatcher.send(Message::Terminate).unwrap();
+    /// Like spawn_with_priority, but doesn't block on a full bounded queue
+    /// and doesn't run f inline on a pool without workers,
+    /// None if f could not be queued, it is then dropped without running
+    pub(crate) fn try_spawn_with_priority<F, T>(&'scope self, priority: Priority, f: F)
+        -> Option<JobHandle<T>>
+    where
+        F: FnOnce() -> T + Send + 'scope,
+        T: Send + 'scope,
+    {
+        if self.pool.manual.is_none() && self.pool.workers.is_empty() {
+            return None;
         }
+        let (job, handle) = self.scoped_job(f);
+        // the job handed back in the error is dropped here, ending its part in the scope
+        self.pool.try_dispatch(job, priority).ok().map(|_| { handle })
+    }
+
+    /// f as a job counted as pending in the scope until it is run or dropped
+    fn scoped_job<F, T>(&'scope self, f: F)
+        -> (Job, JobHandle<T>)
+    where
+        F: FnOnce() -> T + Send + 'scope,
+        T: Send + 'scope,
+    {
+        let (job, handle) = job_with_handle(f);
+        let job: Box<dyn FnOnce() + Send + 'scope> = Box::new(job);
+        // SAFETY: ThreadPool::scope does not return before every ScopedJob
+        // is dropped, and the job is dropped before the ScopedJob is
+        let job: Job = unsafe { mem::transmute(job) };
 
+        *self.state.pending.lock().unwrap_or_else(PoisonError::into_inner) += 1;
+        let scoped = ScopedJob { job: Some(job), state: Arc::clone(&self.state) };
+        (Box::new(move | | { scoped.run() }), handle)
+    }
+}
+
+impl ThreadPool
+{
+    /// Snapshot of what every worker did so far, jobs run inline are not counted
+    #[cfg(feature = "stats")]
+    pub fn stats(&self)
+        -> PoolStats
+    {
+        PoolStats {
+            workers: self.shared.stats.iter()
+                .map(|x| { x.lock().unwrap_or_else(PoisonError::into_inner).clone() })
+                .collect(),
+        }
+    }
+
+    /// Block until no job is queued or running
+    /// Don't call from inside a job, it would wait for itself
+    pub fn wait_idle(&self)
+    {
+        if self.manual.is_some() {
+            self.run_until_idle();
+            return;
+        }
+        self.shared.wait_idle(None);
+    }
+
+    /// Token that is cancelled when the pool drops its pending jobs,
+    /// long running jobs can poll it to stop early
+    pub fn cancellation_token(&self)
+        -> CancellationToken
+    {
+        self.token.clone()
+    }
+
+    /// Drop every queued job, then wait for the running ones
+    /// Handles of the dropped jobs report JobError::Cancelled
+    pub fn shutdown_now(mut self)
+    {
+        self.discard_pending();
+        self.shutdown(None);
+    }
+
+    /// Let queued jobs finish for at most timeout, then drop what is left
+    /// and stop waiting for workers still busy, they finish detached
+    /// Returns true if every job finished in time
+    pub fn shutdown_timeout(mut self, timeout: Duration)
+        -> bool
+    {
+        self.shutdown(Some(self.now() + timeout))
+    }
+
+    fn discard_pending(&self)
+    {
+        self.shared.discarding.store(true, Ordering::Release);
+        self.token.cancel();
+        if let Some(manual) = &self.manual {
+            manual.clear();
+        }
+    }
+
+    /// Stop taking jobs and join the workers once the queue is drained
+    /// Past the deadline the pending jobs are dropped, before the workers
+    /// are detached so none of them starts another one
+    /// Never panics, workers that died are ignored
+    fn shutdown(&mut self, deadline: Option<Instant>)
+        -> bool
+    {
+        // workers leave their loop once the queue is empty and disconnected
+        if self.dispatcher.take().is_none() {
+            return true;
+        }
+        if let Some(manual) = &self.manual {
+            // stand in for the workers, a job that moves the clock past
+            // the deadline is one that did not finish in time
+            loop {
+                if deadline.is_some_and(|x| { manual.clock().now() > x }) {
+                    self.discard_pending();
+                    return false;
+                }
+                if self.step().is_none() {
+                    return true;
+                }
+            }
+        }
+        if !self.shared.wait_idle(deadline) {
+            self.discard_pending();
+            // dropping the handles detaches the threads
+            self.workers.clear();
+            return false;
+        }
+
+        // a worker dying pushes its replacement before its thread ends,
+        // so once the originals are joined every replacement is listed
         for worker in &mut self.workers {
             if let Some(thread) = worker.thread.take() {
-                thread.join()
-                    .expect(&format!("can't join thread {}", worker.id));
+                let _ = thread.join();
             }
         }
+        loop {
+            let worker = self.shared.respawned.lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .pop();
+            match worker {
+                Some(Worker { thread: Some(thread) }) => { let _ = thread.join(); },
+                Some(_) => {},
+                None => break,
+            }
+        }
+        #[cfg(feature = "stats")]
+        if self.shared.print_stats {
+            eprint!("{}", self.stats());
+        }
+        true
+    }
+}
+
+impl ThreadPool
+{
+    fn manual(&self)
+        -> &ManualQueue
+    {
+        self.manual.as_ref().expect("not a manual pool, see ThreadPoolBuilder::manual")
+    }
+
+    /// Current time, on the FakeClock for a manual pool
+    fn now(&self)
+        -> Instant
+    {
+        self.manual.as_ref().map_or_else(Instant::now, |x| { x.clock().now() })
+    }
+
+    /// Run the next queued job of a manual pool on this thread,
+    /// None if nothing is queued
+    /// Joining a handle of a job that was not stepped yet blocks forever
+    pub fn step(&self)
+        -> Option<Submission>
+    {
+        let (submission, job) = self.manual().pop()?;
+        run_job(job);
+        Some(submission)
+    }
+
+    /// Step until the queue of a manual pool is empty, including the jobs
+    /// queued while doing so, returns how many ran
+    pub fn run_until_idle(&self)
+        -> usize
+    {
+        let mut count = 0;
+        while self.step().is_some() {
+            count += 1;
+        }
+        count
+    }
+
+    /// Jobs waiting in the queue of a manual pool
+    pub fn queued(&self)
+        -> usize
+    {
+        self.manual().len()
+    }
+
+    /// Every job handed to a manual pool so far, in submission order
+    pub fn submissions(&self)
+        -> Vec<Submission>
+    {
+        self.manual().log()
+    }
+}
+
+impl Drop for ThreadPool
+{
+    /// Waits for every queued job, never panics
+    fn drop(&mut self) {
+        self.shutdown(None);
     }
 }
 
@@ -101,34 +1158,33 @@ mod test
     use std::time;
 
     #[test]
-    /// test time of unthreaded pool is correct
+    /// test unthreaded pool runs the job before execute returns
     fn test_0x001()
     {
         let pool = ThreadPool::new(0);
-        let func = | | {
-            thread::sleep(time::Duration::from_millis(10));
-        };
-        let now = time::Instant::now();
-
-        pool.execute(func);
-        assert!(now.elapsed() >= time::Duration::from_millis(10));
+        let (transmitter, reciever) = mpsc::channel();
+        pool.execute(move | | { transmitter.send(()).unwrap(); });
+        assert_eq!(reciever.try_recv(), Ok(()));
     }
 
     #[test]
-    /// test time of threaded pool is correct
+    /// test threaded pool returns from execute at once and waits on drop
     fn test_0x002()
     {
-        let now = time::Instant::now();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        let done = Arc::new(AtomicBool::new(false));
         {
             let pool = ThreadPool::new(2);
-            let func = | | {
-                thread::sleep(time::Duration::from_millis(10));
-            };
-
-            pool.execute(func);
-            assert!(now.elapsed() <= time::Duration::from_millis(10));
+            let job_done = Arc::clone(&done);
+            pool.execute(move | | {
+                release_rx.recv().unwrap();
+                job_done.store(true, Ordering::SeqCst);
+            });
+            // the job is stuck until released, so execute did not wait for it
+            assert!(!done.load(Ordering::SeqCst));
+            release_tx.send(()).unwrap();
         }
-        assert!(now.elapsed() >= time::Duration::from_millis(10));
+        assert!(done.load(Ordering::SeqCst));
     }
 
     #[test]
@@ -138,16 +1194,462 @@ mod test
         let pool = ThreadPool::new(2);
         let a = 2;
         let b = 3;
+        let (release_tx, release_rx) = mpsc::channel::<()>();
         let (transmitter, reciever) = mpsc::channel();
         let func = move | | {
-            thread::sleep(time::Duration::from_millis(10));
+            release_rx.recv().unwrap();
             transmitter.send(a + b).unwrap();
         };
-        let now = time::Instant::now();
 
         pool.execute(func);
-        assert!(now.elapsed() <= time::Duration::from_millis(10));
+        assert_eq!(reciever.try_recv(), Err(mpsc::TryRecvError::Empty));
+        release_tx.send(()).unwrap();
         assert_eq!(reciever.recv().unwrap(), 5);
-        assert!(now.elapsed() >= time::Duration::from_millis(10));
+    }
+
+    #[test]
+    /// test result through job handle
+    fn test_0x004()
+    {
+        let pool = ThreadPool::new(2);
+        let a = 2;
+        let b = 3;
+        let handle = pool.submit(move | | { a * b });
+        assert_eq!(handle.join().unwrap(), 6);
+
+        let inline = ThreadPool::new(0);
+        let handle = inline.submit(| | { "done" });
+        assert_eq!(handle.try_join().unwrap().unwrap(), "done");
+    }
+
+    #[test]
+    /// test map keeps item order
+    fn test_0x005()
+    {
+        let pool = ThreadPool::new(4);
+        let squares = pool.map(0..100u64, |x| {
+            thread::sleep(time::Duration::from_micros(100 - x));
+            x * x
+        });
+        assert_eq!(squares, (0..100u64).map(|x| { x * x }).collect::<Vec<_>>());
+        assert_eq!(pool.map(Vec::<u64>::new(), |x| { x }), Vec::<u64>::new());
+    }
+
+    #[test]
+    /// test panic is reported through the handle and the pool survives
+    fn test_0x006()
+    {
+        for size in [0, 1] {
+            let pool = ThreadPool::new(size);
+            let handle = pool.submit(| | -> usize { panic!("bad job") });
+            let error = handle.join().unwrap_err();
+            assert!(matches!(error, JobError::Panicked(_)));
+            assert_eq!(error.to_string(), "job panicked: bad job");
+
+            pool.execute(| | { panic!("bad job") });
+            assert_eq!(pool.submit(| | { 1 + 1 }).join().unwrap(), 2);
+        }
+    }
+
+    #[test]
+    /// test dead worker is respawned and drop does not panic
+    fn test_0x007()
+    {
+        struct PanicOnDrop;
+        impl Drop for PanicOnDrop {
+            fn drop(&mut self) {
+                panic!("payload dropped");
+            }
+        }
+
+        let pool = ThreadPool::new(1);
+        for _ in 0..3 {
+            pool.execute(| | { panic::panic_any(PanicOnDrop) });
+        }
+        assert_eq!(pool.submit(| | { 3 }).join().unwrap(), 3);
+        drop(pool);
+    }
+
+    #[test]
+    /// test scoped jobs borrow from the stack
+    fn test_0x008()
+    {
+        for size in [0, 3] {
+            let pool = ThreadPool::new(size);
+            let table = vec![1, 2, 3, 4];
+            let mut written = vec![0; 4];
+            let total = pool.scope(|s| {
+                let handles: Vec<JobHandle<usize>> = table.iter()
+                    .map(|x| { s.spawn(move | | { x * 10 }) })
+                    .collect();
+                for (i, slot) in written.iter_mut().enumerate() {
+                    let table = &table;
+                    s.spawn(move | | { *slot = table[i] + 1; });
+                }
+                handles.into_iter().map(|x| { x.join().unwrap() }).sum::<usize>()
+            });
+            assert_eq!(total, 100);
+            assert_eq!(written, vec![2, 3, 4, 5]);
+        }
+    }
+
+    #[test]
+    /// test scope waits for all jobs even if they are never joined
+    fn test_0x009()
+    {
+        let pool = ThreadPool::new(2);
+        let counter = std::sync::atomic::AtomicUsize::new(0);
+        pool.scope(|s| {
+            for _ in 0..20 {
+                s.spawn(| | {
+                    thread::sleep(time::Duration::from_millis(1));
+                    counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                });
+            }
+            s.spawn(| | { panic!("contained") });
+        });
+        assert_eq!(counter.into_inner(), 20);
+    }
+
+    #[test]
+    /// test builder configuration reaches the workers
+    fn test_0x00a()
+    {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(2)
+            .thread_name("solver")
+            .stack_size(8 * 1024 * 1024)
+            .queue_capacity(1)
+            .build();
+        assert_eq!(pool.size(), 2);
+        let name = pool.submit(| | {
+            thread::current().name().map(|x| { x.to_string() })
+        }).join().unwrap();
+        assert!(name.unwrap().starts_with("solver "));
+
+        // a full queue only blocks, it does not lose jobs
+        let (transmitter, reciever) = mpsc::channel();
+        for i in 0..10 {
+            let transmitter = transmitter.clone();
+            pool.execute(move | | { transmitter.send(i).unwrap(); });
+        }
+        drop(transmitter);
+        assert_eq!(reciever.iter().sum::<i32>(), 45);
+    }
+
+    #[test]
+    /// test default size follows AOC_THREADS, then available parallelism
+    fn test_0x00b()
+    {
+        assert_eq!(default_num_threads(Some("3")), 3);
+        assert_eq!(default_num_threads(Some(" 0\n")), 0);
+        assert_eq!(default_num_threads(None), thread::available_parallelism().unwrap().get());
+        assert_eq!(ThreadPoolBuilder::new().num_threads(1).build().size(), 1);
+    }
+
+    #[test]
+    /// test full bounded queue hands the job back
+    fn test_0x00c()
+    {
+        let pool = ThreadPoolBuilder::new().num_threads(1).queue_capacity(1).build();
+        let (started_tx, started_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        let (done_tx, done_rx) = mpsc::channel();
+
+        pool.execute(move | | {
+            started_tx.send(()).unwrap();
+            release_rx.recv().unwrap();
+        });
+        started_rx.recv().unwrap();
+
+        let done = done_tx.clone();
+        assert!(pool.try_execute(move | | { done.send("queued").unwrap(); }).is_ok());
+        let done = done_tx.clone();
+        let error = pool.try_execute(move | | { done.send("returned").unwrap(); })
+            .unwrap_err();
+        assert!(matches!(error, TryExecuteError::Full(_)));
+
+        error.into_job()();
+        release_tx.send(()).unwrap();
+        drop(pool);
+        drop(done_tx);
+        assert_eq!(done_rx.iter().collect::<Vec<_>>(), vec!["returned", "queued"]);
+    }
+
+    #[test]
+    /// test wait_idle waits for queued jobs without dropping the pool
+    fn test_0x00d()
+    {
+        let pool = ThreadPool::new(2);
+        let counter = Arc::new(AtomicUsize::new(0));
+        for _ in 0..50 {
+            let counter = Arc::clone(&counter);
+            pool.execute(move | | {
+                thread::sleep(time::Duration::from_micros(200));
+                counter.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+        pool.wait_idle();
+        assert_eq!(counter.load(Ordering::SeqCst), 50);
+
+        // still usable afterwards
+        assert_eq!(pool.submit(| | { 7 }).join().unwrap(), 7);
+        pool.wait_idle();
+        ThreadPool::new(0).wait_idle();
+    }
+
+    #[test]
+    /// test shutdown_now drops what is still queued
+    fn test_0x00e()
+    {
+        let pool = ThreadPool::new(1);
+        let token = pool.cancellation_token();
+        let (started_tx, started_rx) = mpsc::channel();
+        let running = pool.submit(move | | {
+            started_tx.send(()).unwrap();
+            while !token.is_cancelled() {
+                thread::yield_now();
+            }
+            "stopped"
+        });
+        started_rx.recv().unwrap();
+        let queued = pool.submit(| | { "ran" });
+        pool.shutdown_now();
+        assert_eq!(running.join().unwrap(), "stopped");
+        assert!(matches!(queued.join(), Err(JobError::Cancelled)));
+    }
+
+    #[test]
+    /// test shutdown_timeout gives up on jobs that don't finish
+    fn test_0x00f()
+    {
+        let pool = ThreadPool::new(2);
+        pool.execute(| | {});
+        assert!(pool.shutdown_timeout(Duration::from_secs(10)));
+
+        let pool = ThreadPool::new(1);
+        let token = pool.cancellation_token();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        pool.execute(move | | { let _ = release_rx.recv(); });
+        let queued = pool.submit(| | {});
+        assert!(!pool.shutdown_timeout(Duration::from_millis(10)));
+        assert!(token.is_cancelled());
+        release_tx.send(()).unwrap();
+        assert!(matches!(queued.join(), Err(JobError::Cancelled)));
+    }
+
+    #[test]
+    /// test a token shared by a search stops the other jobs
+    fn test_0x010()
+    {
+        let pool = ThreadPool::new(4);
+        let token = CancellationToken::new();
+        let found = pool.map(0..8u64, move |x| {
+            let mut i = x;
+            loop {
+                if token.is_cancelled() {
+                    return None;
+                }
+                if i == 1000 {
+                    token.cancel();
+                    return Some(x);
+                }
+                i += 8;
+            }
+        });
+        assert_eq!(found.iter().flatten().count(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "stats")]
+    /// test stats count every job once
+    fn test_0x011()
+    {
+        let pool = ThreadPool::new(3);
+        for _ in 0..30 {
+            pool.execute(| | { thread::sleep(time::Duration::from_micros(100)); });
+        }
+        pool.wait_idle();
+        let stats = pool.stats();
+        assert_eq!(stats.workers.len(), 3);
+        assert_eq!(stats.jobs(), 30);
+        assert_eq!(stats.run_time().count(), 30);
+        assert!(stats.run_time().total() >= time::Duration::from_millis(3));
+        assert!(stats.to_string().starts_with("worker"));
+    }
+
+    #[test]
+    /// test queued jobs are taken by priority, then submission order
+    fn test_0x012()
+    {
+        let pool = ThreadPool::new(1);
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        pool.execute(move | | { release_rx.recv().unwrap(); });
+
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let jobs = [
+            (Priority::Low, "low"),
+            (Priority::Normal, "normal 1"),
+            (Priority::High, "high"),
+            (Priority::Normal, "normal 2"),
+        ];
+        for (priority, name) in jobs {
+            let order = Arc::clone(&order);
+            pool.execute_with_priority(priority, move | | {
+                order.lock().unwrap().push(name);
+            });
+        }
+        release_tx.send(()).unwrap();
+        pool.wait_idle();
+        assert_eq!(*order.lock().unwrap(), vec!["high", "normal 1", "normal 2", "low"]);
+    }
+
+    #[test]
+    /// test manual pool runs nothing until stepped, then by priority
+    fn test_0x013()
+    {
+        let clock = FakeClock::new();
+        let pool = ThreadPoolBuilder::new().manual(clock.clone()).build();
+        let ran = Arc::new(Mutex::new(Vec::new()));
+        let priorities = [Priority::Low, Priority::Normal, Priority::High, Priority::Normal];
+        for (i, priority) in priorities.into_iter().enumerate() {
+            let ran = Arc::clone(&ran);
+            pool.execute_with_priority(priority, move | | { ran.lock().unwrap().push(i); });
+            clock.advance(time::Duration::from_millis(1));
+        }
+        assert!(ran.lock().unwrap().is_empty());
+        assert_eq!(pool.queued(), 4);
+
+        let log = pool.submissions();
+        assert_eq!(log.iter().map(|x| { x.seq }).collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+        assert_eq!(log.iter().map(|x| { x.priority }).collect::<Vec<_>>(), priorities);
+        assert_eq!(log[3].at - log[0].at, time::Duration::from_millis(3));
+
+        assert_eq!(pool.step().map(|x| { x.seq }), Some(2));
+        assert_eq!(*ran.lock().unwrap(), vec![2]);
+        assert_eq!(pool.run_until_idle(), 3);
+        assert_eq!(pool.step(), None);
+        assert_eq!(*ran.lock().unwrap(), vec![2, 1, 3, 0]);
+    }
+
+    #[test]
+    /// test manual pool runs scoped jobs and jobs queued by jobs
+    fn test_0x014()
+    {
+        let pool = ThreadPoolBuilder::new().manual(FakeClock::new()).build();
+        let values = [1, 2, 3];
+        let total = pool.scope(|s| {
+            let handles: Vec<_> = values.iter().map(|x| { s.spawn(move | | { x * 10 }) }).collect();
+            // nothing ran yet, joining here would block forever
+            assert!(handles.iter().all(|x| { x.try_join().is_none() }));
+            handles
+        });
+        let total: i32 = total.into_iter().map(|x| { x.join().unwrap() }).sum();
+        assert_eq!(total, 60);
+
+        let pool = Arc::new(pool);
+        let inner = Arc::clone(&pool);
+        let handle = pool.submit(move | | { inner.submit(| | { 7 }) });
+        pool.wait_idle();
+        assert_eq!(handle.join().unwrap().join().unwrap(), 7);
+        assert_eq!(pool.submissions().len(), 5);
+    }
+
+    #[test]
+    /// test shutdown_timeout of manual pool follows the fake clock
+    fn test_0x015()
+    {
+        let clock = FakeClock::new();
+        let pool = ThreadPoolBuilder::new().manual(clock.clone()).build();
+        let token = pool.cancellation_token();
+        let handles: Vec<_> = (0..4)
+            .map(|x| {
+                let clock = clock.clone();
+                pool.submit(move | | {
+                    clock.advance(time::Duration::from_millis(5));
+                    x
+                })
+            })
+            .collect();
+
+        // 5 and 10 are in time, 15 is past the deadline
+        assert!(!pool.shutdown_timeout(time::Duration::from_millis(12)));
+        assert!(token.is_cancelled());
+        let results: Vec<_> = handles.into_iter().map(JobHandle::join).collect();
+        assert!(matches!(results[..3], [Ok(0), Ok(1), Ok(2)]));
+        assert!(matches!(results[3], Err(JobError::Cancelled)));
+
+        let pool = ThreadPoolBuilder::new().manual(clock.clone()).build();
+        pool.execute(move | | { clock.advance(time::Duration::from_millis(5)); });
+        assert!(pool.shutdown_timeout(time::Duration::from_millis(5)));
+    }
+
+    #[test]
+    /// test wait_idle waits for a job that queued a job that finished first
+    fn test_0x016()
+    {
+        let pool = Arc::new(ThreadPool::new(2));
+        let done = Arc::new(AtomicUsize::new(0));
+        for i in 1..=100 {
+            let inner = Arc::clone(&pool);
+            let parent_done = Arc::clone(&done);
+            pool.execute(move | | {
+                let child_done = Arc::clone(&parent_done);
+                inner.execute(move | | { child_done.fetch_add(1, Ordering::SeqCst); });
+                // give the child time to finish before the parent does
+                thread::yield_now();
+                parent_done.fetch_add(1, Ordering::SeqCst);
+            });
+            pool.wait_idle();
+            assert_eq!(done.load(Ordering::SeqCst), 2 * i);
+        }
+    }
+
+    #[test]
+    /// test shutdown_timeout drops queued jobs once the deadline passed
+    fn test_0x017()
+    {
+        let pool = ThreadPool::new(1);
+        let token = pool.cancellation_token();
+        let running = pool.submit(move | | {
+            while !token.is_cancelled() {
+                thread::yield_now();
+            }
+        });
+        let queued = pool.submit(| | { 1 });
+        assert!(!pool.shutdown_timeout(time::Duration::from_millis(20)));
+        assert!(running.join().is_ok());
+        assert!(matches!(queued.join(), Err(JobError::Cancelled)));
+    }
+
+    #[test]
+    /// test a zero capacity queue hands jobs to idle workers
+    fn test_0x018()
+    {
+        let pool = ThreadPoolBuilder::new().num_threads(2).queue_capacity(0).build();
+        let (transmitter, reciever) = mpsc::channel();
+        let deadline = Instant::now() + time::Duration::from_secs(5);
+        // the workers may not be waiting yet right after the build
+        let mut queued = 0;
+        while queued < 20 && Instant::now() < deadline {
+            let transmitter = transmitter.clone();
+            match pool.try_execute(move | | { transmitter.send(()).unwrap(); }) {
+                Ok(()) => queued += 1,
+                Err(TryExecuteError::Full(_)) => thread::yield_now(),
+                Err(error) => panic!("{}", error),
+            }
+        }
+        assert_eq!(queued, 20);
+        drop(transmitter);
+        pool.wait_idle();
+        assert_eq!(reciever.iter().count(), 20);
+    }
+
+    #[test]
+    /// test the shared pool is built once
+    fn test_0x019()
+    {
+        assert!(std::ptr::eq(ThreadPool::shared(), ThreadPool::shared()));
+        assert_eq!(ThreadPool::shared().submit(| | { 3 }).join().unwrap(), 3);
     }
 }
@@ -0,0 +1,296 @@
+//! Tasks with dependencies, each started as soon as its predecessors finish
+
+use std::any::Any;
+use std::panic::{
+    self, AssertUnwindSafe,
+};
+use std::sync::{
+    Mutex, PoisonError,
+};
+use std::sync::atomic::{
+    AtomicUsize, Ordering,
+};
+
+use super::{
+    Priority, Scope, ThreadPool,
+};
+
+type Task<'a> = Box<dyn FnOnce() + Send + 'a>;
+type Payload = Box<dyn Any + Send>;
+
+/// Refers to a task in a TaskGraph, used to declare dependencies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TaskId(usize);
+
+struct Node<'a>
+{
+    task: Mutex<Option<Task<'a>>>,
+    /// dependencies that have not finished, the task starts at zero
+    remaining: AtomicUsize,
+    dependents: Vec<usize>,
+}
+
+/// Tasks that may borrow from the caller, each one runs after its dependencies
+/// Dependencies have to be added first, so the graph can't have cycles
+#[derive(Default)]
+pub struct TaskGraph<'a>
+{
+    nodes: Vec<Node<'a>>,
+}
+
+impl<'a> TaskGraph<'a>
+{
+    pub fn new()
+        -> TaskGraph<'a>
+    {
+        TaskGraph { nodes: Vec::new() }
+    }
+
+    pub fn len(&self)
+        -> usize
+    {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self)
+        -> bool
+    {
+        self.nodes.is_empty()
+    }
+
+    /// Add a task that runs once every task in dependencies has finished
+    pub fn add_task<F>(&mut self, dependencies: &[TaskId], f: F)
+        -> TaskId
+    where
+        F: FnOnce() + Send + 'a,
+    {
+        let id = self.nodes.len();
+        let mut remaining = 0;
+        for &TaskId(dependency) in dependencies {
+            assert!(dependency < id, "dependency {} is not in the graph", dependency);
+            let dependents = &mut self.nodes[dependency].dependents;
+            // listing a dependency twice must not count it twice
+            if !dependents.contains(&id) {
+                dependents.push(id);
+                remaining += 1;
+            }
+        }
+        self.nodes.push(Node {
+            task: Mutex::new(Some(Box::new(f))),
+            remaining: AtomicUsize::new(remaining),
+            dependents: Vec::new(),
+        });
+        TaskId(id)
+    }
+
+    /// Run every task on the pool and return once all are done
+    /// Tasks without dependencies are queued with Normal priority, the rest
+    /// with High priority when they become ready, so started work is finished
+    /// before new work is taken up.
+    /// A task that finds a bounded queue full is run right away by the thread
+    /// that made it ready, so workers never block on each other
+    /// If a task panics its dependents are skipped and the panic is resumed here
+    pub fn run(self, pool: &ThreadPool)
+    {
+        let nodes = self.nodes;
+        // collected up front, dependents reach zero while roots are spawned
+        let roots: Vec<usize> = (0..nodes.len())
+            .filter(|&id| { nodes[id].remaining.load(Ordering::Relaxed) == 0 })
+            .collect();
+        let panicked = Mutex::new(None);
+
+        pool.scope(|scope| {
+            for id in roots {
+                spawn_node(scope, &nodes, &panicked, id, Priority::Normal);
+            }
+        });
+
+        if let Some(payload) = panicked.into_inner().unwrap_or_else(PoisonError::into_inner) {
+            panic::resume_unwind(payload);
+        }
+    }
+}
+
+/// Queue the node, or run it right here if it can't be queued
+fn spawn_node<'scope, 'env, 'a: 'scope>(
+    scope: &'scope Scope<'scope, 'env>,
+    nodes: &'scope [Node<'a>],
+    panicked: &'scope Mutex<Option<Payload>>,
+    id: usize,
+    priority: Priority,
+)
+{
+    if !try_queue_node(scope, nodes, panicked, id, priority) {
+        run_nodes(scope, nodes, panicked, id);
+    }
+}
+
+/// False if the pool would run the node inline or its queue is full
+fn try_queue_node<'scope, 'env, 'a: 'scope>(
+    scope: &'scope Scope<'scope, 'env>,
+    nodes: &'scope [Node<'a>],
+    panicked: &'scope Mutex<Option<Payload>>,
+    id: usize,
+    priority: Priority,
+)
+    -> bool
+{
+    scope.try_spawn_with_priority(priority, move | | {
+        run_nodes(scope, nodes, panicked, id);
+    }).is_some()
+}
+
+/// Run the node and every dependent that becomes ready but can't be queued,
+/// from a worklist so a long chain run inline doesn't grow the stack
+fn run_nodes<'scope, 'env, 'a: 'scope>(
+    scope: &'scope Scope<'scope, 'env>,
+    nodes: &'scope [Node<'a>],
+    panicked: &'scope Mutex<Option<Payload>>,
+    id: usize,
+)
+{
+    let mut ready = vec![id];
+    while let Some(id) = ready.pop() {
+        let task = nodes[id].task.lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+            .expect("task runs once");
+        if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(task)) {
+            let mut panicked = panicked.lock().unwrap_or_else(PoisonError::into_inner);
+            // keep the first, the rest are likely consequences of it
+            if panicked.is_none() {
+                *panicked = Some(payload);
+            }
+            continue;
+        }
+
+        for &dependent in &nodes[id].dependents {
+            if nodes[dependent].remaining.fetch_sub(1, Ordering::AcqRel) == 1
+                && !try_queue_node(scope, nodes, panicked, dependent, Priority::High)
+            {
+                ready.push(dependent);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use super::super::{
+        FakeClock, ThreadPoolBuilder,
+    };
+    use std::sync::{
+        Arc, Barrier,
+    };
+
+    /// Run the r1 -> c1, r2 graph and return the order tasks ran in
+    fn run_order(pool: &ThreadPool)
+        -> Vec<&'static str>
+    {
+        let order = Mutex::new(Vec::new());
+        let mut graph = TaskGraph::new();
+        let r1 = graph.add_task(&[], | | { order.lock().unwrap().push("r1"); });
+        graph.add_task(&[], | | { order.lock().unwrap().push("r2"); });
+        graph.add_task(&[r1], | | { order.lock().unwrap().push("c1"); });
+        graph.run(pool);
+        order.into_inner().unwrap()
+    }
+
+    #[test]
+    /// test a ready dependent runs before queued roots, also on an inline
+    /// and a manual pool
+    fn test_0x001()
+    {
+        assert_eq!(run_order(&ThreadPool::new(1)), vec!["r1", "c1", "r2"]);
+        assert_eq!(run_order(&ThreadPool::new(0)), vec!["r1", "c1", "r2"]);
+        let manual = ThreadPoolBuilder::new().manual(FakeClock::new()).build();
+        assert_eq!(run_order(&manual), vec!["r1", "c1", "r2"]);
+    }
+
+    #[test]
+    /// test a task starts only after all its dependencies have ended
+    fn test_0x002()
+    {
+        let pool = ThreadPool::new(4);
+        let events = Mutex::new(Vec::new());
+        // a and b run at the same time, so c can't start early by luck
+        let barrier = Arc::new(Barrier::new(2));
+        let mut graph = TaskGraph::new();
+        let a = graph.add_task(&[], | | {
+            barrier.wait();
+            events.lock().unwrap().push("a end");
+        });
+        let b = graph.add_task(&[], | | {
+            barrier.wait();
+            events.lock().unwrap().push("b end");
+        });
+        let c = graph.add_task(&[a, b], | | { events.lock().unwrap().push("c"); });
+        graph.add_task(&[a, c, c], | | { events.lock().unwrap().push("d"); });
+        graph.run(&pool);
+
+        let events = events.into_inner().unwrap();
+        assert_eq!(events.len(), 4);
+        assert_eq!(&events[2..], &["c", "d"]);
+    }
+
+    #[test]
+    /// test a panicking task skips its dependents and the panic reaches run
+    fn test_0x003()
+    {
+        let pool = ThreadPool::new(2);
+        let ran = Mutex::new(Vec::new());
+        let mut graph = TaskGraph::new();
+        let a = graph.add_task(&[], | | { panic!("task a"); });
+        graph.add_task(&[a], | | { ran.lock().unwrap().push("after a"); });
+        graph.add_task(&[], | | { ran.lock().unwrap().push("independent"); });
+
+        let result = panic::catch_unwind(AssertUnwindSafe(| | { graph.run(&pool) }));
+        let payload = result.unwrap_err();
+        assert_eq!(payload.downcast_ref::<&str>(), Some(&"task a"));
+        assert_eq!(ran.into_inner().unwrap(), vec!["independent"]);
+    }
+
+    #[test]
+    /// test a wide graph on a pool whose queues are nearly always full
+    fn test_0x004()
+    {
+        let pool = ThreadPoolBuilder::new().num_threads(2).queue_capacity(1).build();
+        let count = AtomicUsize::new(0);
+        let mut graph = TaskGraph::new();
+        let roots: Vec<TaskId> = (0..20)
+            .map(|_| { graph.add_task(&[], | | { count.fetch_add(1, Ordering::SeqCst); }) })
+            .collect();
+        for &root in &roots {
+            for _ in 0..10 {
+                graph.add_task(&[root], | | { count.fetch_add(1, Ordering::SeqCst); });
+            }
+        }
+        graph.add_task(&roots, | | { count.fetch_add(1, Ordering::SeqCst); });
+        graph.run(&pool);
+        assert_eq!(count.into_inner(), 20 + 20 * 10 + 1);
+    }
+
+    #[test]
+    /// test a long chain run inline or on a full queue doesn't overflow the stack
+    fn test_0x005()
+    {
+        let pools = [
+            ThreadPool::new(0),
+            ThreadPoolBuilder::new().num_threads(1).queue_capacity(1).build(),
+        ];
+        for pool in &pools {
+            let count = AtomicUsize::new(0);
+            let mut graph = TaskGraph::new();
+            let mut previous = graph.add_task(&[], | | { count.fetch_add(1, Ordering::SeqCst); });
+            for _ in 0..100_000 {
+                previous = graph.add_task(&[previous], | | {
+                    count.fetch_add(1, Ordering::SeqCst);
+                });
+            }
+            graph.run(pool);
+            assert_eq!(count.into_inner(), 100_001);
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! Deterministic executor for tests, jobs wait in the queue until the test
+//! runs them one at a time on its own thread
+
+use std::collections::VecDeque;
+use std::mem;
+use std::sync::{
+    Arc, Mutex, MutexGuard, PoisonError,
+};
+use std::time::{
+    Duration, Instant,
+};
+
+use super::{
+    Job, Priority, NUM_PRIORITIES,
+};
+
+/// Clock that only moves when told to, clones share the same time
+#[derive(Debug, Clone)]
+pub struct FakeClock
+{
+    now: Arc<Mutex<Instant>>,
+}
+
+impl Default for FakeClock
+{
+    fn default()
+        -> FakeClock
+    {
+        FakeClock::new()
+    }
+}
+
+impl FakeClock
+{
+    pub fn new()
+        -> FakeClock
+    {
+        FakeClock { now: Arc::new(Mutex::new(Instant::now())) }
+    }
+
+    pub fn now(&self)
+        -> Instant
+    {
+        *self.now.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Move the time forward, jobs call this to pretend they take a while
+    pub fn advance(&self, duration: Duration)
+    {
+        *self.now.lock().unwrap_or_else(PoisonError::into_inner) += duration;
+    }
+}
+
+/// A job as it was handed to a manual pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Submission
+{
+    /// position in the submission log, counting from 0
+    pub seq: usize,
+    pub priority: Priority,
+    /// time on the pool's FakeClock
+    pub at: Instant,
+}
+
+/// Queue of a manual pool, taken in the same order workers would take it
+pub(super) struct ManualQueue
+{
+    clock: FakeClock,
+    state: Mutex<ManualState>,
+}
+
+#[derive(Default)]
+struct ManualState
+{
+    queues: [VecDeque<(Submission, Job)>; NUM_PRIORITIES],
+    log: Vec<Submission>,
+}
+
+impl ManualQueue
+{
+    pub(super) fn new(clock: FakeClock)
+        -> ManualQueue
+    {
+        ManualQueue { clock, state: Mutex::new(ManualState::default()) }
+    }
+
+    pub(super) fn clock(&self)
+        -> &FakeClock
+    {
+        &self.clock
+    }
+
+    fn state(&self)
+        -> MutexGuard<'_, ManualState>
+    {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub(super) fn push(&self, job: Job, priority: Priority)
+    {
+        let mut state = self.state();
+        let submission = Submission {
+            seq: state.log.len(),
+            priority,
+            at: self.clock.now(),
+        };
+        state.log.push(submission);
+        state.queues[priority as usize].push_back((submission, job));
+    }
+
+    /// Highest priority job, first in first out within a priority
+    pub(super) fn pop(&self)
+        -> Option<(Submission, Job)>
+    {
+        self.state().queues.iter_mut().find_map(VecDeque::pop_front)
+    }
+
+    pub(super) fn len(&self)
+        -> usize
+    {
+        self.state().queues.iter().map(VecDeque::len).sum()
+    }
+
+    pub(super) fn log(&self)
+        -> Vec<Submission>
+    {
+        self.state().log.clone()
+    }
+
+    /// Drop every queued job
+    pub(super) fn clear(&self)
+    {
+        let queues = mem::take(&mut self.state().queues);
+        // dropping a scoped job locks its scope, so not while holding ours
+        drop(queues);
+    }
+}
@@ -0,0 +1,224 @@
+//! Per worker metrics, only compiled with the `stats` feature
+
+use std::fmt;
+use std::time::{
+    Duration, Instant,
+};
+
+const NUM_BUCKETS: usize = 40;
+
+/// Durations bucketed by powers of two nanoseconds
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Histogram
+{
+    buckets: [u64; NUM_BUCKETS],
+    count: u64,
+    total: Duration,
+    max: Duration,
+}
+
+impl Default for Histogram
+{
+    fn default()
+        -> Histogram
+    {
+        Histogram {
+            buckets: [0; NUM_BUCKETS],
+            count: 0,
+            total: Duration::ZERO,
+            max: Duration::ZERO,
+        }
+    }
+}
+
+impl Histogram
+{
+    pub fn record(&mut self, duration: Duration)
+    {
+        let nanos = duration.as_nanos().max(1);
+        let bucket = (127 - nanos.leading_zeros() as usize).min(NUM_BUCKETS - 1);
+        self.buckets[bucket] += 1;
+        self.count += 1;
+        self.total += duration;
+        self.max = self.max.max(duration);
+    }
+
+    pub fn count(&self)
+        -> u64
+    {
+        self.count
+    }
+
+    pub fn total(&self)
+        -> Duration
+    {
+        self.total
+    }
+
+    pub fn max(&self)
+        -> Duration
+    {
+        self.max
+    }
+
+    pub fn mean(&self)
+        -> Duration
+    {
+        if self.count == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_nanos((self.total.as_nanos() / self.count as u128) as u64)
+    }
+
+    /// Non empty buckets as (lower bound, upper bound, count), the last
+    /// bucket takes everything above its lower bound so it has no upper bound
+    pub fn buckets(&self)
+        -> impl Iterator<Item = (Duration, Option<Duration>, u64)> + '_
+    {
+        self.buckets.iter().enumerate()
+            .filter(|(_, count)| { **count > 0 })
+            .map(|(i, count)| {
+                let high = (i + 1 < NUM_BUCKETS).then(| | { Duration::from_nanos(1 << (i + 1)) });
+                (Duration::from_nanos(1 << i), high, *count)
+            })
+    }
+
+    pub fn merge(&mut self, other: &Histogram)
+    {
+        for (bucket, count) in self.buckets.iter_mut().zip(other.buckets.iter()) {
+            *bucket += count;
+        }
+        self.count += other.count;
+        self.total += other.total;
+        self.max = self.max.max(other.max);
+    }
+}
+
+/// What one worker has been up to
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorkerStats
+{
+    /// jobs run to completion or panic, dropped jobs are not counted
+    pub jobs: u64,
+    /// time from a job being queued until this worker started it
+    pub queue_wait: Histogram,
+    pub run_time: Histogram,
+    /// time spent waiting for the queue to hand out a job
+    pub idle: Duration,
+}
+
+impl WorkerStats
+{
+    pub(super) fn record(&mut self, idle_since: Instant, queued_at: Instant, started: Instant)
+    {
+        self.jobs += 1;
+        self.idle += started.saturating_duration_since(idle_since);
+        self.queue_wait.record(started.saturating_duration_since(queued_at));
+        self.run_time.record(started.elapsed());
+    }
+}
+
+/// Snapshot of every worker, from ThreadPool::stats
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PoolStats
+{
+    pub workers: Vec<WorkerStats>,
+}
+
+impl PoolStats
+{
+    pub fn jobs(&self)
+        -> u64
+    {
+        self.workers.iter().map(|x| { x.jobs }).sum()
+    }
+
+    /// Queue wait of all workers together
+    pub fn queue_wait(&self)
+        -> Histogram
+    {
+        self.workers.iter().fold(Histogram::default(), |mut acc, x| {
+            acc.merge(&x.queue_wait);
+            acc
+        })
+    }
+
+    /// Run time of all workers together
+    pub fn run_time(&self)
+        -> Histogram
+    {
+        self.workers.iter().fold(Histogram::default(), |mut acc, x| {
+            acc.merge(&x.run_time);
+            acc
+        })
+    }
+}
+
+impl fmt::Display for PoolStats
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>)
+        -> fmt::Result
+    {
+        writeln!(f, "{:>6} {:>10} {:>12} {:>12} {:>12}",
+            "worker", "jobs", "busy", "idle", "avg wait")?;
+        for (id, worker) in self.workers.iter().enumerate() {
+            writeln!(f, "{:>6} {:>10} {:>12?} {:>12?} {:>12?}",
+                id,
+                worker.jobs,
+                worker.run_time.total(),
+                worker.idle,
+                worker.queue_wait.mean())?;
+        }
+        writeln!(f, "run time of {} jobs:", self.jobs())?;
+        for (low, high, count) in self.run_time().buckets() {
+            match high {
+                Some(high) => writeln!(f, "  {:>12?} .. {:<12?} {}", low, high, count)?,
+                None => writeln!(f, "  {:>12} {:<12?} {}", ">=", low, count)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use super::*;
+
+    #[test]
+    /// test histogram buckets and totals
+    fn test_0x001()
+    {
+        let mut histogram = Histogram::default();
+        histogram.record(Duration::from_nanos(1));
+        histogram.record(Duration::from_nanos(3));
+        histogram.record(Duration::from_nanos(2));
+        histogram.record(Duration::from_secs(1000));
+        assert_eq!(histogram.count(), 4);
+        assert_eq!(histogram.max(), Duration::from_secs(1000));
+        let buckets: Vec<_> = histogram.buckets().collect();
+        assert_eq!(buckets[0], (Duration::from_nanos(1), Some(Duration::from_nanos(2)), 1));
+        assert_eq!(buckets[1], (Duration::from_nanos(2), Some(Duration::from_nanos(4)), 2));
+        assert_eq!(buckets[2], (Duration::from_nanos(1 << 39), None, 1));
+
+        let mut merged = Histogram::default();
+        merged.merge(&histogram);
+        merged.merge(&histogram);
+        assert_eq!(merged.count(), 8);
+        assert_eq!(merged.total(), histogram.total() * 2);
+    }
+
+    #[test]
+    /// test the last bucket is printed without an upper bound
+    fn test_0x002()
+    {
+        let mut worker = WorkerStats::default();
+        worker.run_time.record(Duration::from_nanos(3));
+        worker.run_time.record(Duration::from_secs(1000));
+        let stats = PoolStats { workers: vec![worker] };
+        let printed = stats.to_string();
+        assert!(printed.contains("2ns .. 4ns"));
+        assert!(printed.contains(&format!(">= {:?}", Duration::from_nanos(1 << 39))));
+        assert!(!printed.contains(&format!("{:?}", Duration::from_nanos(1 << 40))));
+    }
+}
@@ -0,0 +1,70 @@
+use aoclib::solution::{Answer, Solution};
+
+pub struct Day1;
+
+impl Solution for Day1
+{
+    type Input = Vec<i32>;
+
+    fn day(&self)
+        -> u32
+    {
+        1
+    }
+
+    fn parse(&self, input: &str)
+        -> Vec<i32>
+    {
+        input.split('\n')
+            .filter(|x| {!x.is_empty()})
+            .map(|x| {
+                x.parse::<i32>().expect("can't convert string to int")
+            })
+            .collect()
+    }
+
+    fn part1(&self, depths: &Vec<i32>)
+        -> Answer
+    {
+        count_increases(depths, 1).into()
+    }
+
+    fn part2(&self, depths: &Vec<i32>)
+        -> Answer
+    {
+        count_increases(depths, 3).into()
+    }
+}
+
+/// Number of times the sum of window consecutive depths increases
+fn count_increases(depths: &[i32], window: usize)
+    -> usize
+{
+    let sums: Vec<i32> = depths.windows(window)
+        .map(|x| { x.iter().sum() })
+        .collect();
+    sums.windows(2).filter(|x| { x[1] > x[0] }).count()
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use aoclib::answers;
+
+    #[test]
+    /// test the example depths
+    fn test_0x0001()
+    {
+        let depths = Day1.parse("199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n");
+        assert_eq!(Day1.part1(&depths), Answer::from(7));
+        assert_eq!(Day1.part2(&depths), Answer::from(5));
+    }
+
+    #[test]
+    /// test input.txt and test_input.txt against answers.toml
+    fn test_0x0002()
+    {
+        answers::assert_answers(&Day1, env!("CARGO_MANIFEST_DIR"));
+    }
+}
@@ -0,0 +1,231 @@
+use aoclib::parallel::ParallelSlice;
+use aoclib::solution::{Answer, Solution};
+use aoclib::threadpool::ThreadPool;
+use itertools::Itertools;
+
+pub struct Day10;
+
+impl Solution for Day10
+{
+    type Input = Vec<String>;
+
+    fn day(&self)
+        -> u32
+    {
+        10
+    }
+
+    fn parse(&self, input: &str)
+        -> Vec<String>
+    {
+        input.lines().map(String::from).collect()
+    }
+
+    fn part1(&self, lines: &Vec<String>)
+        -> Answer
+    {
+        find_errors(lines).iter().map( | x | {
+            if let LineFix::Corrupted(corrupted) = x {
+                score_corruption(corrupted)
+            } else {
+                0
+            }
+        }).sum::<usize>().into()
+    }
+
+    fn part2(&self, lines: &Vec<String>)
+        -> Answer
+    {
+        score_completions(&find_errors(lines)).into()
+    }
+}
+
+/// What is wrong with each line, lines are checked on their own
+/// so they are spread over the shared pool
+fn find_errors(lines: &[String])
+    -> Vec<LineFix>
+{
+    lines.par(ThreadPool::shared()).par_map(|x| { find_error(x) })
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Bracket
+{
+    Round,   //Parentheses,
+    Square,  //Bracket,
+    Curly,   //Brace,
+    Angular, //Chevron,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Chunk
+{
+    Open(Bracket),
+    Close(Bracket),
+    Invalid,
+}
+
+impl Chunk
+{
+    fn new(c: char)
+        -> Chunk
+    {
+        match c 
+        {
+        '(' => Chunk::Open(Bracket::Round),
+        ')' => Chunk::Close(Bracket::Round),
+        '[' => Chunk::Open(Bracket::Square),
+        ']' => Chunk::Close(Bracket::Square),
+        '{' => Chunk::Open(Bracket::Curly),
+        '}' => Chunk::Close(Bracket::Curly),
+        '<' => Chunk::Open(Bracket::Angular),
+        '>' => Chunk::Close(Bracket::Angular),
+        _ => Chunk::Invalid,
+        }
+    }
+}
+
+pub enum LineFix
+{
+    Corrupted(Chunk),
+    Incomplete(Vec<Bracket>),
+}
+
+fn find_error(line: &str)
+    -> LineFix
+{
+    let mut stack = Vec::new();
+    for c in line.chars() {
+        let chunk = Chunk::new(c);
+
+        match chunk
+        {
+        Chunk::Open(b) => {
+            stack.push(b)
+        },
+        Chunk::Close(b) => {
+            if let Some(top) = stack.pop() {
+                if top != b {
+                    return LineFix::Corrupted(Chunk::Close(b));
+                }
+            }
+            else {
+                return LineFix::Corrupted(Chunk::Close(b));
+            }
+        },
+        Chunk::Invalid => {
+            unreachable!("send umiddelbar hjelp");
+        },
+        }
+    }
+    LineFix::Incomplete(stack)
+}
+
+fn score_corruption(error_chunk: &Chunk)
+    -> usize
+{
+    match error_chunk
+    {
+    Chunk::Close(Bracket::Round) => 3,
+    Chunk::Close(Bracket::Square) => 57,
+    Chunk::Close(Bracket::Curly) => 1197,
+    Chunk::Close(Bracket::Angular) => 25137,
+    _ => 0,
+    }
+}
+
+fn score_completions(lines: &[LineFix])
+    -> usize
+{
+    let scores: Vec<usize> = lines.iter().filter( | x | {
+            matches!(x, LineFix::Incomplete(_))
+        }).map(|x| {
+            if let LineFix::Incomplete(stack) = x {
+                get_completion_score(stack)
+            } else {
+                panic!();
+            }
+        }).sorted().collect();
+    assert!(scores.len() % 2 == 1);
+    scores[scores.len() / 2]
+}
+
+fn get_completion_score(stack: &[Bracket])
+    -> usize
+{
+    stack.iter().rev().fold(0, |acc, x| {
+        let x_score = match x
+            {
+            Bracket::Round => 1,
+            Bracket::Square => 2,
+            Bracket::Curly => 3,
+            Bracket::Angular => 4,
+            };
+        acc*5 + x_score
+    })
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use aoclib::answers;
+    use aoclib::fileops;
+
+    fn test_input()
+        -> Vec<String>
+    {
+        Day10.parse(&fileops::read_file("test_input.txt"))
+    }
+
+    #[test]
+    /// test finding correct corruptions
+    fn test_0x0001()
+    {
+        let fixes = find_errors(&test_input());
+        let corruptions = vec![
+                Chunk::Invalid,
+                Chunk::Invalid,
+                Chunk::Close(Bracket::Curly),
+                Chunk::Invalid,
+                Chunk::Close(Bracket::Round),
+                Chunk::Close(Bracket::Square),
+                Chunk::Invalid,
+                Chunk::Close(Bracket::Round),
+                Chunk::Close(Bracket::Angular),
+                Chunk::Invalid,
+            ];
+        for (i, fix) in fixes.iter().enumerate() {
+            if let LineFix::Corrupted(error) = fix {
+                eprintln!("got corrupted {:?}", error);
+                assert_eq!(error, &corruptions[i]);
+            }
+        }
+
+    }
+
+    #[test]
+    /// test finding correct corruptions
+    fn test_0x0002()
+    {
+        let lines = test_input();
+        assert_eq!(Day10.part1(&lines), Answer::from(26397));
+    }
+
+    #[test]
+    /// test finding correct completion scores
+    fn test_0x0003()
+    {
+        let lines = test_input();
+        let score = score_completions(&find_errors(&lines));
+        assert_eq!(score, 288957);
+        assert_eq!(Day10.part2(&lines), Answer::from(288957));
+    }
+
+    #[test]
+    /// test input.txt and test_input.txt against answers.toml
+    fn test_0x0004()
+    {
+        answers::assert_answers(&Day10, env!("CARGO_MANIFEST_DIR"));
+    }
+}
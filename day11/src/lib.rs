@@ -0,0 +1,149 @@
+use aoclib::gridops;
+use aoclib::solution::{Answer, Solution};
+use ndarray::prelude::*;
+
+pub struct Day11;
+
+impl Solution for Day11
+{
+    type Input = Array2<usize>;
+
+    fn day(&self)
+        -> u32
+    {
+        11
+    }
+
+    fn parse(&self, input: &str)
+        -> Array2<usize>
+    {
+        gridops::parse_grid(input)
+    }
+
+    fn part1(&self, grid: &Array2<usize>)
+        -> Answer
+    {
+        let mut grid = grid.clone();
+        let mut flashes = 0;
+        for _ in 0..100 {
+            flashes += simulate_step(&mut grid);
+        }
+        flashes.into()
+    }
+
+    fn part2(&self, grid: &Array2<usize>)
+        -> Answer
+    {
+        let mut grid = grid.clone();
+        let mut i = 0;
+        while grid != Array2::<usize>::zeros((10,10)) {
+            simulate_step(&mut grid);
+            i += 1;
+        }
+        i.into()
+    }
+}
+
+fn simulate_step(grid: &mut Array2<usize>)
+    -> usize
+{
+    for value in grid.iter_mut() {
+        *value += 1;
+    }
+
+    let who_flashed = flash(grid, vec![]);
+
+    for flasher in &who_flashed {
+        grid[*flasher] = 0;
+    }
+
+    who_flashed.len()
+}
+
+fn flash(grid: &mut Array2<usize>
+        , mut who_flashed: Vec<(usize,usize)>)
+    -> Vec<(usize,usize)>
+{
+    let mut someone_flashed = false;
+    let mut new_flashers = Vec::new();
+
+    for (idx, value) in grid.indexed_iter() {
+        if *value > 9 && !who_flashed.contains(&idx) {
+            new_flashers.push(idx);
+            someone_flashed = true;
+        }
+    }
+
+    for flasher in &new_flashers {
+        let sorounding = gridops::find_sorounding(grid, *flasher);
+        for point in sorounding {
+            grid[point] += 1;
+        }
+    }
+
+    who_flashed.extend(new_flashers);
+    if someone_flashed {
+        who_flashed = flash(grid, who_flashed);
+    }
+    who_flashed
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use aoclib::answers;
+    use aoclib::fileops;
+
+    fn test_input()
+        -> Array2<usize>
+    {
+        Day11.parse(&fileops::read_file("test_input.txt"))
+    }
+
+    #[test]
+    /// early iterations
+    fn test_0x0001()
+    {
+        let mut grid = test_input();
+        let mut flashes = 0;
+        flashes += simulate_step(&mut grid);
+        assert_eq!(flashes, 0);
+        flashes += simulate_step(&mut grid);
+        assert_eq!(flashes, 35);
+        flashes += simulate_step(&mut grid);
+        assert_eq!(flashes, 35+45);
+    }
+
+    #[test]
+    /// early iterations
+    fn test_0x0002()
+    {
+        let mut grid = test_input();
+        let mut flashes = 0;
+        for _ in 0..100 {
+            flashes += simulate_step(&mut grid);
+        }
+        assert_eq!(flashes, 1656);
+        assert_eq!(Day11.part1(&test_input()), Answer::from(1656));
+    }
+
+    #[test]
+    /// test synchronizing assumption in text
+    fn test_0x0003()
+    {
+        let mut grid = test_input();
+        for _ in 0..195 {
+            simulate_step(&mut grid);
+        }
+        assert_eq!(grid, Array2::<usize>::zeros((10,10)));
+        assert_eq!(Day11.part2(&test_input()), Answer::from(195));
+    }
+
+    #[test]
+    /// test input.txt and test_input.txt against answers.toml
+    fn test_0x0004()
+    {
+        answers::assert_answers(&Day11, env!("CARGO_MANIFEST_DIR"));
+    }
+}
@@ -0,0 +1,97 @@
+use aoclib::solution::{Answer, Solution};
+use itertools::Itertools;
+
+pub struct Day2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction
+{
+    Forward,
+    Down,
+    Up,
+}
+
+impl Solution for Day2
+{
+    type Input = Vec<(Direction, i32)>;
+
+    fn day(&self)
+        -> u32
+    {
+        2
+    }
+
+    fn parse(&self, input: &str)
+        -> Vec<(Direction, i32)>
+    {
+        input.lines().map(|line| {
+            let (direction, length) = line.split(' ').next_tuple()
+                .expect("less than two elements");
+            let direction = match direction {
+                "forward" => Direction::Forward,
+                "down" => Direction::Down,
+                "up" => Direction::Up,
+                &_ => unreachable!("undefined direction!")
+            };
+            (direction, length.parse::<i32>().unwrap())
+        }).collect()
+    }
+
+    fn part1(&self, commands: &Vec<(Direction, i32)>)
+        -> Answer
+    {
+        let mut x = 0;
+        let mut y = 0;
+        for &(direction, length) in commands {
+            match direction {
+                Direction::Forward => x += length,
+                Direction::Down => y += length,
+                Direction::Up => y -= length,
+            }
+        }
+        (x*y).into()
+    }
+
+    fn part2(&self, commands: &Vec<(Direction, i32)>)
+        -> Answer
+    {
+        let mut pos = 0;
+        let mut depth = 0;
+        let mut aim = 0;
+        for &(direction, v) in commands {
+            match direction {
+                Direction::Down => aim += v,
+                Direction::Up => aim -= v,
+                Direction::Forward => {
+                    pos += v;
+                    depth += aim * v;
+                },
+            }
+        }
+        (pos*depth).into()
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use aoclib::answers;
+
+    #[test]
+    /// test the example course
+    fn test_0x0001()
+    {
+        let commands = Day2.parse("forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2\n");
+        assert_eq!(commands[1], (Direction::Down, 5));
+        assert_eq!(Day2.part1(&commands), Answer::from(150));
+        assert_eq!(Day2.part2(&commands), Answer::from(900));
+    }
+
+    #[test]
+    /// test input.txt and test_input.txt against answers.toml
+    fn test_0x0002()
+    {
+        answers::assert_answers(&Day2, env!("CARGO_MANIFEST_DIR"));
+    }
+}
@@ -0,0 +1,147 @@
+use aoclib::bitops::{BitMatrix, BitVec};
+use aoclib::solution::{Answer, Solution};
+
+pub struct Day3;
+
+impl Solution for Day3
+{
+    type Input = BitMatrix;
+
+    fn day(&self)
+        -> u32
+    {
+        3
+    }
+
+    /// One row per line, the width is that of the first line and the
+    /// other lines have to match it
+    fn parse(&self, input: &str)
+        -> BitMatrix
+    {
+        BitMatrix::parse_lines(input.lines()).expect("not a binary report")
+    }
+
+    fn part1(&self, report: &BitMatrix)
+        -> Answer
+    {
+        power_consumption(report).into()
+    }
+
+    fn part2(&self, report: &BitMatrix)
+        -> Answer
+    {
+        life_support_rating(report).into()
+    }
+}
+
+fn power_consumption(report: &BitMatrix)
+    -> u64
+{
+    let (gamma_rate, epsilon_rate) = find_power_rates(report);
+    let gamma_rate: u64 = gamma_rate.to_num().expect("too many bits");
+    let epsilon_rate: u64 = epsilon_rate.to_num().expect("too many bits");
+    gamma_rate * epsilon_rate
+}
+
+fn life_support_rating(report: &BitMatrix)
+    -> u64
+{
+    let oxygen: u64 = find_rating(report, true).to_num().expect("too many bits");
+    let scrubber: u64 = find_rating(report, false).to_num().expect("too many bits");
+    oxygen * scrubber
+}
+
+/// Most and least common bit of every column
+fn find_power_rates(report: &BitMatrix)
+    -> (BitVec, BitVec)
+{
+    let mut most_common_bits = BitVec::zeros(report.cols());
+    for (col, ones) in report.column_counts().into_iter().enumerate() {
+        most_common_bits.set(col, ones >= report.rows() - ones);
+    }
+    let least_common_bits = !&most_common_bits;
+    (most_common_bits, least_common_bits)
+}
+
+/// Narrow down the rows column by column, keeping those with the most
+/// common bit, or the least common one, until one row is left
+/// Ties keep the ones for the most common bit and the zeros for the least
+fn find_rating(report: &BitMatrix, most_common: bool)
+    -> BitVec
+{
+    // one row per column, so each step works on whole words
+    let columns = report.transpose();
+    let mut candidates = BitVec::ones(report.rows());
+    for col in 0..report.cols() {
+        let remaining = candidates.count_ones();
+        if remaining <= 1 {
+            break;
+        }
+        let column = columns.row(col);
+        let ones = (&column & &candidates).count_ones();
+        let zeros = remaining - ones;
+        let keep_ones = match (ones, zeros) {
+            (0, _) => false,
+            (_, 0) => true,
+            _ if most_common => ones >= zeros,
+            _ => ones < zeros,
+        };
+        if keep_ones {
+            candidates &= &column;
+        } else {
+            candidates &= &!&column;
+        }
+    }
+    let row = candidates.iter_ones().next().expect("no rows in the report");
+    report.row(row)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use aoclib::answers;
+    use aoclib::bitops::BitError;
+    use aoclib::fileops;
+
+    #[test]
+    /// test the 5 bit sample
+    fn test_0x0001()
+    {
+        let report = Day3.parse(&fileops::read_file("test_input.txt"));
+        assert_eq!(report.cols(), 5);
+        let (gamma_rate, epsilon_rate) = find_power_rates(&report);
+        assert_eq!((gamma_rate.to_string(), epsilon_rate.to_string()),
+            ("10110".to_string(), "01001".to_string()));
+        assert_eq!(Day3.part1(&report), Answer::from(198));
+
+        assert_eq!(find_rating(&report, true).to_string(), "10111");
+        assert_eq!(find_rating(&report, false).to_string(), "01010");
+        assert_eq!(Day3.part2(&report), Answer::from(230));
+    }
+
+    #[test]
+    /// test the 12 bit input
+    fn test_0x0002()
+    {
+        let report = Day3.parse(&fileops::read_file("input.txt"));
+        assert_eq!(report.cols(), 12);
+        assert_eq!(Day3.part1(&report), Answer::from(841526));
+        assert_eq!(Day3.part2(&report), Answer::from(4790390));
+    }
+
+    #[test]
+    /// test rows of different width are rejected
+    fn test_0x0003()
+    {
+        let report = BitMatrix::parse_lines(["00100", "11110", "1011"]);
+        assert_eq!(report, Err(BitError::WidthMismatch { row: 2, expected: 5, found: 4 }));
+    }
+
+    #[test]
+    /// test input.txt and test_input.txt against answers.toml
+    fn test_0x0004()
+    {
+        answers::assert_answers(&Day3, env!("CARGO_MANIFEST_DIR"));
+    }
+}
@@ -0,0 +1,247 @@
+use aoclib::solution::{Answer, Solution};
+
+#[derive(Debug,Clone,Copy)]
+struct BingoEntry
+{
+    value: u32,
+    matched: bool,
+}
+
+#[derive(Clone)]
+struct BingoBoard
+{
+    board: Vec<BingoEntry>,
+    score: u32,
+    winturn: u32,
+}
+
+/// The numbers in the order they are drawn and the unmarked boards
+pub struct Bingo
+{
+    draws: Vec<u32>,
+    boards: Vec<BingoBoard>,
+}
+
+pub struct Day4;
+
+impl Solution for Day4
+{
+    type Input = Bingo;
+
+    fn day(&self)
+        -> u32
+    {
+        4
+    }
+
+    fn parse(&self, input: &str)
+        -> Bingo
+    {
+        get_bingo_boards(input)
+    }
+
+    fn part1(&self, bingo: &Bingo)
+        -> Answer
+    {
+        best_board_score(bingo).into()
+    }
+
+    fn part2(&self, bingo: &Bingo)
+        -> Answer
+    {
+        last_board_score(bingo).into()
+    }
+}
+
+fn best_board_score(bingo: &Bingo)
+    -> u32
+{
+    let draws = &bingo.draws;
+    let mut boards = bingo.boards.clone();
+    for board in &mut boards {
+        calculate_board_score(draws, board).unwrap();
+    }
+
+    let initial = BingoBoard {
+        board: Vec::new(), score: 0, winturn: draws.len() as u32,
+    };
+    let best = boards.iter().fold( &initial,
+    |acc, b| {
+        if b.winturn < acc.winturn {
+            b
+        } else {
+            acc
+        }
+    });
+
+    best.score
+}
+
+fn last_board_score(bingo: &Bingo)
+    -> u32
+{
+    let draws = &bingo.draws;
+    let mut boards = bingo.boards.clone();
+    for board in &mut boards {
+        calculate_board_score(draws, board).unwrap();
+    }
+
+    let initial = BingoBoard {
+        board: Vec::new(), score: 0, winturn: 0,
+    };
+    let best = boards.iter().fold( &initial,
+    |acc, b| {
+        if b.winturn > acc.winturn {
+            b
+        } else {
+            acc
+        }
+    });
+
+    best.score
+}
+
+fn calculate_board_score(draws: &[u32], board: &mut BingoBoard)
+    -> Result<(u32, u32), &'static str>
+{
+    for (i, draw) in draws.iter().enumerate() {
+        mark_draw(draw, board);
+        if check_win(board) {
+            board.score = board.board.iter()
+                .filter(|x| { !x.matched })
+                .fold(0, |sum, x| { sum + x.value })
+                * draw;
+            board.winturn = i as u32 + 1;
+            return Ok((board.winturn, board.score));
+        }
+    }
+    Err("Board did not win!")
+}
+
+fn mark_draw(draw: &u32, board: &mut BingoBoard)
+{
+    for entry in &mut board.board {
+        if entry.value == *draw {
+            if entry.matched {
+                panic!("matching on matched number {:?}", entry);
+            }
+            entry.matched = true;
+        }
+    }
+}
+
+fn check_win(board: &mut BingoBoard)
+    -> bool
+{
+    let board_dim = (board.board.len() as f64).sqrt() as usize;
+    let matches: Vec<u8> = board.board.iter().map(|x| { x.matched as u8 }).collect();
+    for i in 0..board_dim {
+        let row = &matches[(i*board_dim) .. ((i+1)*board_dim)];
+        if row.iter().sum::<u8>() == board_dim as u8 {
+            return true;
+        }
+
+        let c = i;
+        let mut c_i = -1;
+        let col: Vec<u8> = matches.iter().filter(|_| {
+                c_i+=1; c_i as usize % board_dim == c
+            }).copied().collect();
+        if col.iter().sum::<u8>() == board_dim as u8 {
+            return true;
+        }
+    }
+
+    false
+}
+
+
+fn get_bingo_boards(input: &str)
+    -> Bingo
+{
+    let mut lines = input.lines();
+    let numberdraws: Vec<u32> = lines.next().unwrap()
+        .split(',')
+        .map(|x| {
+            x.parse::<u32>().expect("could not convert to u32")
+        })
+        .collect();
+    lines.next(); // empty line after draws
+    let mut boards: Vec<BingoBoard> = Vec::new();
+    let mut board_acc: Vec<BingoEntry> = Vec::new();
+    let new_board = |board| { BingoBoard { board, score: 0, winturn: 0 } };
+    for line in lines {
+        match line {
+            "" => {
+                if !board_acc.is_empty() {
+                    boards.push(new_board(board_acc));
+                    board_acc = Vec::new();
+                }
+            },
+            vals => {
+                let bingovals: Vec<BingoEntry> = vals.split(' ')
+                    .filter(|x| {
+                        // if only one digit, two spaces between characters
+                        !x.is_empty()
+                    })
+                    .map(|x| {
+                        BingoEntry {
+                            value: x.parse::<u32>()
+                                .expect("u32 conversion board error"),
+                            matched: false,
+                        }
+                    }).collect();
+                board_acc.extend(bingovals);
+            },
+        }
+    }
+    // the last board doesn't need an empty line after it
+    if !board_acc.is_empty() {
+        boards.push(new_board(board_acc));
+    }
+    Bingo { draws: numberdraws, boards }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use aoclib::answers;
+
+    const EXAMPLE: &str = "\
+7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1
+
+22 13 17 11  0
+ 8  2 23  4 24
+21  9 14 16  7
+ 6 10  3 18  5
+ 1 12 20 15 19
+
+ 3 15  0  2 22
+ 9 18 13 17  5
+19  8  7 25 23
+20 11 10 24  4
+14 21 16 12  6
+
+14 21 17 24  4
+10 16 15  9 19
+18  8 23 26 20
+22 11 13  6  5
+ 2  0 12  3  7";
+
+    #[test]
+    /// test the example boards, without an empty line at the end
+    fn test_0x0001()
+    {
+        let bingo = Day4.parse(EXAMPLE);
+        assert_eq!(bingo.boards.len(), 3);
+        assert_eq!(Day4.part1(&bingo), Answer::from(4512));
+        assert_eq!(Day4.part2(&bingo), Answer::from(1924));
+    }
+
+    #[test]
+    /// test input.txt and test_input.txt against answers.toml
+    fn test_0x0002()
+    {
+        answers::assert_answers(&Day4, env!("CARGO_MANIFEST_DIR"));
+    }
+}
@@ -0,0 +1,262 @@
+use aoclib::threadpool;
+use aoclib::memo::Memo;
+use aoclib::solution::{Answer, Solution};
+
+//const HORIZON: usize = 78; //tuning parameter
+const HORIZON: usize = 120; //tuning parameter
+
+const WORKER_STACK_SIZE: usize = 32 * 1024 * 1024;
+const QUEUE_CAPACITY: usize = 1024; // one job per fish, don't queue them all up front
+
+type LookupTable = [Vec<u8>];
+/// population size by (fish, iterations left)
+type ForecastMemo = Memo<(u8, usize), usize>;
+
+pub struct Day6;
+
+impl Solution for Day6
+{
+    type Input = Vec<u8>;
+
+    fn day(&self)
+        -> u32
+    {
+        6
+    }
+
+    fn parse(&self, input: &str)
+        -> Vec<u8>
+    {
+        input.lines()
+            .next().unwrap()
+            .split(',')
+            .map(|x| {
+                x.parse::<u8>().expect("not a number")
+            })
+            .collect()
+    }
+
+    fn part1(&self, population: &Vec<u8>)
+        -> Answer
+    {
+        population_after(population, 80).into()
+    }
+
+    fn part2(&self, population: &Vec<u8>)
+        -> Answer
+    {
+        population_after(population, 256).into()
+    }
+}
+
+fn population_after(population: &[u8], total_steps: usize)
+    -> usize
+{
+    let lookup = create_lookup();
+    let first_steps = total_steps % HORIZON;
+    let population = grow_population(population.to_vec(), first_steps);
+    forecast_population(population, total_steps/HORIZON, &lookup)
+}
+
+fn grow_population(mut population: Vec<u8>, steps: usize)
+    -> Vec<u8>
+{
+    for _ in 0 .. steps {
+        let old_len = population.len();
+        for i in 0 .. old_len {
+            if population[i] == 0 {
+                population.push(8);
+                population[i] = 6;
+            } else {
+                population[i] -= 1;
+            }
+        }
+    }
+    population
+}
+
+fn create_lookup()
+    -> Vec<Vec<u8>>
+{
+    (0 ..= 8).map(|i| { grow_population(vec![i], HORIZON) }).collect()
+}
+
+fn grow_population_lookup(
+    mut population: Vec<u8>,
+    iterations: usize,
+    lookup:     &LookupTable,
+)
+    -> Vec<u8>
+{
+    let mut tmp = Vec::new();
+    for _ in 0..iterations {
+        for fish in population {
+            tmp.extend(&lookup[fish as usize]);
+        }
+        population = tmp;
+        tmp = Vec::new();
+    }
+    population
+}
+
+fn forecast_population(
+    population: Vec<u8>,
+    iterations: usize,
+    lookup:     &LookupTable,
+)
+    -> usize
+{
+    // a fish's offspring only depends on its timer, so few sizes are computed
+    let memo = ForecastMemo::new();
+    forecast_population_recursion(population, iterations, 0, lookup, &memo)
+
+    // is slower
+    //return forecast_population_recursion_thread_split(population, iterations, lookup);
+}
+
+#[allow(unused)]
+fn forecast_population_recursion_thread_split(
+    population: Vec<u8>,
+    iterations: usize,
+    lookup:     &LookupTable,
+)
+    -> usize
+{
+    // the jobs start one iteration in
+    if iterations == 0 {
+        return population.len();
+    }
+    // used to overflow the default worker stack above HORIZON 80
+    let memo = ForecastMemo::new();
+    let threadpool = threadpool::ThreadPoolBuilder::new()
+        .stack_size(WORKER_STACK_SIZE)
+        .queue_capacity(QUEUE_CAPACITY)
+        .build();
+    threadpool.scope(|s| {
+        let handles: Vec<_> = population.iter()
+            .map(|&fish| {
+                let memo = &memo;
+                s.spawn(move | | {
+                    forecast_population_recursion(
+                        grow_population_lookup(vec![fish], 1, lookup),
+                        iterations,
+                        1,
+                        lookup,
+                        memo,
+                    )
+                })
+            })
+            .collect();
+        handles.into_iter()
+            .map(|x| { x.join().expect("forecast panicked") })
+            .sum()
+    })
+}
+
+fn forecast_population_recursion(
+    population: Vec<u8>,
+    iterations: usize,
+    current:    usize,
+    lookup:     &LookupTable,
+    memo:       &ForecastMemo,
+)
+    -> usize
+{
+    if current == iterations {
+        return population.len();
+    }
+
+    let mut population_size = 0;
+
+    for fish in population {
+        population_size += memo.get_or_insert_with((fish, iterations - current), | | {
+            forecast_population_recursion(
+                grow_population_lookup(vec![fish], 1, lookup),
+                iterations,
+                current + 1,
+                lookup,
+                memo,
+            )
+        });
+    }
+
+    population_size
+}
+
+//fn forecast_population_recursion_thread_split(
+//        mut population: Vec<u8>,
+//        steps:          usize,
+//        split:          usize,
+//        current:        usize,
+//        limit:          usize,
+//)
+//    -> Vec<u8>
+//{
+//    let (tx, rx) = mpsc::channel::<Vec<u8>>();
+//    let mut handles = Vec::new();
+//
+//    let size = population.len() / split;
+//    println!("splitting at {}", current);
+//
+//    for i in 0 .. split {
+//        let tx_clone = tx.clone();
+//        let fishes = population[i*size .. (i+1)*size].to_vec();
+//        handles.push(thread::spawn(move || {
+//            let population = grow_population_parallell_impl(
+//                fishes,
+//                steps,
+//                split,
+//                current,
+//                limit);
+//            match tx_clone.send(population) {
+//                Ok(_) => println!("worker done"),
+//                Err(e) => println!("error: {}", e),
+//            }
+//        }));
+//    }
+//    population = Vec::new();
+//
+//    for handle in handles {
+//        handle.join().unwrap();
+//    }
+//
+//    while let Ok(fishes) = rx.try_recv() {
+//        println!("{:?}",fishes);
+//        population.extend(fishes);
+//    }
+//
+//    population
+//}
+
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use aoclib::answers;
+
+    #[test]
+    fn test_0x001()
+    {
+        let initial = vec![3,4,3,1,2];
+        let final_population = grow_population(initial, 18);
+        assert_eq!(final_population.len(), 26);
+        let final_population = grow_population(final_population, 80-18);
+        assert_eq!(final_population.len(), 5934);
+    }
+
+    #[test]
+    fn test_0x002()
+    {
+        let population = Day6.parse("3,4,3,1,2");
+        assert_eq!(Day6.part1(&population), Answer::from(5934));
+        assert_eq!(Day6.part2(&population), Answer::from(26984457539u64));
+    }
+
+    #[test]
+    /// test input.txt and test_input.txt against answers.toml
+    fn test_0x003()
+    {
+        answers::assert_answers(&Day6, env!("CARGO_MANIFEST_DIR"));
+    }
+}
@@ -0,0 +1,243 @@
+use aoclib::solution::{Answer, Solution};
+use itertools::{self, Itertools};
+
+/*
+ * TODO just create this mapping as a hashmap or smth and use that to calculate distance
+ * diff  = 0,1,2,3, 4, 5, 6, 7, 8, 9,10,11,12,13,14,15,16
+ * total = 0,1,3,6,10,15,21,28,36,45
+ */
+
+pub struct Day7;
+
+impl Solution for Day7
+{
+    type Input = Vec<usize>;
+
+    fn day(&self)
+        -> u32
+    {
+        7
+    }
+
+    fn parse(&self, input: &str)
+        -> Vec<usize>
+    {
+        let line = input.lines().next().unwrap();
+        line.split(',').map(|x| { x.parse::<usize>().unwrap() } ).collect_vec()
+    }
+
+    fn part1(&self, positions: &Vec<usize>)
+        -> Answer
+    {
+        let (_, fuel) = choose_best_alignment_position(positions, FuelModel::Constant);
+        fuel.into()
+    }
+
+    fn part2(&self, positions: &Vec<usize>)
+        -> Answer
+    {
+        let (_, fuel) = choose_best_alignment_position(positions, FuelModel::Linear);
+        fuel.into()
+    }
+}
+
+enum FuelModel
+{
+    Constant,
+    Linear,
+}
+
+/// Fuel needed to move each distance, built for one input and fuel model
+/// Covers every distance between two positions in 0 ..= max of the input
+struct FuelCostTable
+{
+    costs: Vec<usize>,
+}
+
+impl FuelCostTable
+{
+    fn new(positions: &[usize], fuel_model: &FuelModel)
+        -> FuelCostTable
+    {
+        FuelCostTable { costs: gen_lookup_table(positions, fuel_model) }
+    }
+
+    /// Highest position the table has costs for
+    fn max_position(&self)
+        -> usize
+    {
+        self.costs.len() - 1
+    }
+
+    fn cost(&self, from: usize, to: usize)
+        -> usize
+    {
+        self.costs[from.abs_diff(to)]
+    }
+}
+
+fn choose_best_alignment_position(positions: &[usize], fuel_model: FuelModel)
+    -> (usize, usize)
+{
+    let table = FuelCostTable::new(positions, &fuel_model);
+    let target = greedy_best_search(positions, &fuel_model, &table);
+    let total_diff = find_total_diff(positions, target, &table);
+    (target, total_diff)
+}
+
+fn find_total_diff(positions: &[usize], target: usize, table: &FuelCostTable)
+    -> usize
+{
+    positions.iter().map(|&x| { table.cost(x, target) }).sum()
+}
+
+fn find_median(vec: &[usize])
+    -> usize
+{
+    let sorted: Vec<&usize> = itertools::sorted(vec).collect();
+    *sorted[vec.len()/2]
+}
+
+fn greedy_best_search(positions: &[usize], fuel_model: &FuelModel, table: &FuelCostTable)
+    -> usize
+{
+    if let FuelModel::Constant = fuel_model {
+        return find_median(positions);
+    }
+
+    let mut best = 0;
+    let mut total_cost = usize::MAX;
+    // the best position is never beyond the outermost crab
+    for p in 0..=table.max_position() {
+        let p_cost = find_total_diff(positions, p, table);
+        if p_cost > total_cost {
+            break;
+        }
+        total_cost = p_cost;
+        best = p;
+    }
+    best
+}
+
+fn gen_lookup_table(positions: &[usize], fuel_model: &FuelModel)
+    -> Vec<usize>
+{
+    let max_pos = positions.iter().max().unwrap();
+    let mut table = Vec::with_capacity(*max_pos);
+    table.push(0);
+    let mut cost = 0;
+    for i in 0..*max_pos {
+        table.push(match fuel_model {
+            FuelModel::Constant =>  table[i] + 1,
+            FuelModel::Linear => {
+                cost += 1;
+                table[i] + cost
+            },
+        });
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use aoclib::answers;
+
+    #[test]
+    /// test constant fuel
+    fn test_0x0001()
+    {
+        let input = Day7.parse("16,1,2,0,4,2,7,1,2,14");
+        let (pos, fuel_cost) = choose_best_alignment_position(&input, FuelModel::Constant);
+        println!("pos:{}, fuel_cost:{}", pos, fuel_cost);
+        assert_eq!(pos, 2);
+        assert_eq!(fuel_cost, 37);
+        assert_eq!(Day7.part1(&input), Answer::from(37));
+
+    }
+
+    #[test]
+    /// test fuel cost lookup
+    fn test_0x0002()
+    {
+        let input = vec![16,1,2,0,4,2,7,1,2,14];
+        let constant_lookup = gen_lookup_table(&input, &FuelModel::Constant);
+        assert_eq!(constant_lookup.len(), 17); //0 ..= 16
+        assert_eq!(constant_lookup, vec![0,1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16]);
+
+        let linear_lookup = gen_lookup_table(&input, &FuelModel::Linear);
+        assert_eq!(linear_lookup.len(), 17); //0 ..= 16
+        assert_eq!(linear_lookup[0],  0);
+        assert_eq!(linear_lookup[1],  1);
+        assert_eq!(linear_lookup[2],  1+2);
+        assert_eq!(linear_lookup[3],  1+2+3);
+        assert_eq!(linear_lookup[4],  1+2+3+4);
+        assert_eq!(linear_lookup[5],  1+2+3+4+5);
+        assert_eq!(linear_lookup[6],  1+2+3+4+5+6);
+        assert_eq!(linear_lookup[7],  1+2+3+4+5+6+7);
+        assert_eq!(linear_lookup[8],  1+2+3+4+5+6+7+8);
+        assert_eq!(linear_lookup[9],  1+2+3+4+5+6+7+8+9);
+        assert_eq!(linear_lookup[10], 1+2+3+4+5+6+7+8+9+10);
+        assert_eq!(linear_lookup[11], 1+2+3+4+5+6+7+8+9+10+11);
+        assert_eq!(linear_lookup[12], 1+2+3+4+5+6+7+8+9+10+11+12);
+        assert_eq!(linear_lookup[13], 1+2+3+4+5+6+7+8+9+10+11+12+13);
+        assert_eq!(linear_lookup[14], 1+2+3+4+5+6+7+8+9+10+11+12+13+14);
+        assert_eq!(linear_lookup[15], 1+2+3+4+5+6+7+8+9+10+11+12+13+14+15);
+        assert_eq!(linear_lookup[16], 1+2+3+4+5+6+7+8+9+10+11+12+13+14+15+16);
+
+    }
+
+    #[test]
+    /// test linear fuel
+    fn test_0x0003()
+    {
+        let input = Day7.parse("16,1,2,0,4,2,7,1,2,14");
+        let (pos, fuel_cost) = choose_best_alignment_position(&input, FuelModel::Linear);
+        println!("pos:{}, fuel_cost:{}", pos, fuel_cost);
+        assert_eq!(pos, 5);
+        assert_eq!(fuel_cost, 168);
+        assert_eq!(Day7.part2(&input), Answer::from(168));
+
+    }
+
+    #[test]
+    /// test a larger input after a smaller one, each gets its own table
+    fn test_0x0004()
+    {
+        let (pos, fuel_cost) = choose_best_alignment_position(&[1, 2, 3], FuelModel::Linear);
+        assert_eq!((pos, fuel_cost), (2, 2));
+
+        let input = vec![16,1,2,0,4,2,7,1,2,14];
+        let (pos, fuel_cost) = choose_best_alignment_position(&input, FuelModel::Linear);
+        assert_eq!((pos, fuel_cost), (5, 168));
+    }
+
+    #[test]
+    /// test more crabs than positions, and a table shared between threads
+    fn test_0x0005()
+    {
+        let input = vec![0,0,0,0,0,3,3];
+        let (pos, fuel_cost) = choose_best_alignment_position(&input, FuelModel::Linear);
+        assert_eq!((pos, fuel_cost), (1, 5 + 2 * 3));
+
+        let table = FuelCostTable::new(&input, &FuelModel::Linear);
+        let costs: Vec<usize> = std::thread::scope(|s| {
+            let handles: Vec<_> = (0..=3)
+                .map(|target| {
+                    let (input, table) = (&input, &table);
+                    s.spawn(move | | { find_total_diff(input, target, table) })
+                })
+                .collect();
+            handles.into_iter().map(|x| { x.join().unwrap() }).collect()
+        });
+        assert_eq!(costs, vec![12, 11, 17, 30]);
+    }
+
+    #[test]
+    /// test input.txt and test_input.txt against answers.toml
+    fn test_0x0006()
+    {
+        answers::assert_answers(&Day7, env!("CARGO_MANIFEST_DIR"));
+    }
+}
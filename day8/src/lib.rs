@@ -0,0 +1,247 @@
+use aoclib::bitops::BitSet;
+use aoclib::parallel::ParallelSlice;
+use aoclib::solution::{Answer, Solution};
+use aoclib::threadpool::ThreadPool;
+use itertools::Itertools;
+use std::str::Chars;
+use std::collections::HashMap;
+
+pub struct Day8;
+
+impl Solution for Day8
+{
+    type Input = Vec<Entry>;
+
+    fn day(&self)
+        -> u32
+    {
+        8
+    }
+
+    fn parse(&self, input: &str)
+        -> Vec<Entry>
+    {
+        parse_text(input.lines())
+    }
+
+    fn part1(&self, entries: &Vec<Entry>)
+        -> Answer
+    {
+        let easies = get_easies(entries);
+        easies.len().into()
+    }
+
+    fn part2(&self, entries: &Vec<Entry>)
+        -> Answer
+    {
+        let decoded_outputs = decode_outputs(entries);
+        decoded_outputs.iter().sum::<usize>().into()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+enum Segment
+{
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+}
+
+/// Lit segments of a digit, each Segment is an element
+type Segments = BitSet<7>;
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct Digit
+{
+    segments: Segments,
+}
+
+impl Digit
+{
+    fn new(text_rep: Chars)
+        -> Digit
+    {
+        Digit {
+            segments: text_rep.map(|x| {
+                let segment = match x
+                {
+                    'a' => Segment::A,
+                    'b' => Segment::B,
+                    'c' => Segment::C,
+                    'd' => Segment::D,
+                    'e' => Segment::E,
+                    'f' => Segment::F,
+                    'g' => Segment::G,
+                     x  => unreachable!("Cannot parse char: {}", x),
+                };
+                segment as usize
+            }).collect(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Entry
+{
+    uniques: Vec<Digit>,
+    output: Vec<Digit>,
+}
+
+fn parse_text<'a>(lines: impl Iterator<Item = &'a str>)
+    -> Vec<Entry>
+{
+    let parse_digits = | digits: &str | {
+        digits.split(' ')
+            .filter(|x| { !x.is_empty() })
+            .map(|x| { Digit::new(x.chars()) })
+            .collect()
+    };
+
+    lines.map(|x| {
+        let (uniques, output) = x.split('|').next_tuple()
+            .expect("malformed input");
+        Entry {
+            uniques: parse_digits(uniques),
+            output: parse_digits(output),
+        }
+    }).collect()
+}
+
+                                       //1,7,4,8
+const UNIQUE_NUM_SEGMENTS: [usize; 4] = [2,3,4,7];
+
+fn get_easies(entries: &[Entry])
+    -> Vec<&Digit>
+{
+    let mut ret = Vec::new();
+    for entry in entries {
+        for o in &entry.output{
+            if UNIQUE_NUM_SEGMENTS.contains(&o.segments.len()) {
+                ret.push(o);
+            }
+        }
+    }
+    ret
+}
+
+/// Entries are deduced on their own, so they are spread over the shared pool
+fn decode_outputs(entries: &[Entry])
+    -> Vec<usize>
+{
+    entries.par(ThreadPool::shared()).par_map(decode_output)
+}
+
+fn decode_output(entry: &Entry)
+    -> usize
+{
+    let configuration = find_configuration(&entry.uniques);
+    let mut number = 0;
+
+    for (i, digit) in entry.output.iter().rev().enumerate() {
+        let num = configuration.get(&digit.segments).expect("digit not configured");
+        number += num * (10usize.pow(i as u32));
+    }
+    number
+}
+
+/// The only unique with len segments that matches predicate
+fn find_digit<P>(uniques: &[Digit], len: usize, predicate: P)
+    -> Segments
+where
+    P: Fn(&Segments) -> bool,
+{
+    uniques.iter()
+        .map(|x| { x.segments })
+        .filter(|x| { x.len() == len })
+        .find(predicate)
+        .expect("digit not among the uniques")
+}
+
+/// Which digit each pattern of lit segments shows
+fn find_configuration(uniques: &[Digit])
+    -> HashMap<Segments, usize>
+{
+    // 1, 7, 4 and 8 are the only ones with their number of segments
+    let one = find_digit(uniques, 2, |_| { true });
+    let seven = find_digit(uniques, 3, |_| { true });
+    let four = find_digit(uniques, 4, |_| { true });
+    let eight = find_digit(uniques, 7, |_| { true });
+
+    // of the six segment digits only 9 covers 4, and only 6 misses part of 1
+    let nine = find_digit(uniques, 6, |x| { four.is_subset(x) });
+    let six = find_digit(uniques, 6, |x| { !one.is_subset(x) });
+    let zero = find_digit(uniques, 6, |x| { *x != nine && *x != six });
+
+    // of the five segment digits only 3 covers 1, and only 5 fits in 6
+    let three = find_digit(uniques, 5, |x| { one.is_subset(x) });
+    let five = find_digit(uniques, 5, |x| { x.is_subset(&six) });
+    let two = find_digit(uniques, 5, |x| { *x != three && *x != five });
+
+    [zero, one, two, three, four, five, six, seven, eight, nine].into_iter()
+        .enumerate()
+        .map(|(num, segments)| { (segments, num) })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use aoclib::answers;
+    use aoclib::fileops;
+
+    fn test_input()
+        -> Vec<Entry>
+    {
+        Day8.parse(&fileops::read_file("test_input.txt"))
+    }
+
+    #[test]
+    /// parse text properly
+    fn test_0x0001()
+    {
+        let parsed = test_input();
+        assert_eq!(parsed.len(), 10);
+        assert_eq!(parsed[0].uniques.len(), 10);
+        assert_eq!(parsed[0].uniques[0].segments.len(), 2);
+        assert!(parsed[0].uniques[0].segments.contains(Segment::B as usize));
+        assert!(parsed[0].uniques[0].segments.contains(Segment::E as usize));
+        assert_eq!(parsed[0].output.len(), 4);
+        assert_eq!(parsed[0].output[1].segments.len(), 5);
+        let segments = [Segment::C, Segment::E, Segment::F, Segment::D, Segment::B];
+        assert_eq!(parsed[0].output[1].segments, segments.map(|x| { x as usize }).into_iter().collect());
+    }
+
+    #[test]
+    /// parse count easy
+    fn test_0x0002()
+    {
+        let parsed = test_input();
+        let easies = get_easies(&parsed);
+        assert_eq!(easies.len(), 26);
+        assert_eq!(Day8.part1(&parsed), Answer::from(26));
+    }
+
+    #[test]
+    /// decode outputs
+    fn test_0x0003()
+    {
+        let parsed = test_input();
+        let decoded_outputs = decode_outputs(&parsed);
+        assert_eq!(decoded_outputs[0], 8394);
+        assert_eq!(decoded_outputs[1], 9781);
+        assert_eq!(decoded_outputs[2], 1197);
+        assert_eq!(Day8.part2(&parsed), Answer::from(61229));
+    }
+
+    #[test]
+    /// test input.txt and test_input.txt against answers.toml
+    fn test_0x0004()
+    {
+        answers::assert_answers(&Day8, env!("CARGO_MANIFEST_DIR"));
+    }
+}
@@ -0,0 +1,170 @@
+use aoclib::gridops::{self, Connectivity, Comparison};
+use aoclib::solution::{Answer, Solution};
+use itertools::Itertools;
+use ndarray::prelude::*;
+use std::collections::HashMap;
+
+pub struct Day9;
+
+impl Solution for Day9
+{
+    type Input = Array2<usize>;
+
+    fn day(&self)
+        -> u32
+    {
+        9
+    }
+
+    fn parse(&self, input: &str)
+        -> Array2<usize>
+    {
+        gridops::parse_grid(input)
+    }
+
+    fn part1(&self, map: &Array2<usize>)
+        -> Answer
+    {
+        let lowest_points = find_lowest_points(map);
+        calc_risk(map, &lowest_points).into()
+    }
+
+    fn part2(&self, map: &Array2<usize>)
+        -> Answer
+    {
+        let lowest_points = find_lowest_points(map);
+        let basins = find_basins(map, &lowest_points);
+        calc_biggest_basin_prod(&basins).into()
+    }
+}
+
+fn find_lowest_points(map: &Array2<usize>)
+    -> Vec<(usize, usize)>
+{
+    gridops::local_minima(map, Connectivity::Four, Comparison::Strict)
+}
+
+/// Every cell but the 9s belongs to the basin of the low point it drains to
+fn find_basins(map: &Array2<usize>, lowest_points: &[(usize, usize)])
+    -> Vec<Vec<(usize, usize)>>
+{
+    let drains = gridops::drain_map(map, Connectivity::Four);
+    let basin_of: HashMap<(usize, usize), usize> = lowest_points.iter()
+        .enumerate()
+        .map(|(i, point)| { (*point, i) })
+        .collect();
+    let mut basins = vec![Vec::new(); lowest_points.len()];
+    for (point, sink) in drains.indexed_iter() {
+        if map[point] == 9 {
+            continue;
+        }
+        if let Some(&i) = basin_of.get(sink) {
+            basins[i].push(point);
+        }
+    }
+    basins
+}
+
+fn calc_biggest_basin_prod(basins: &[Vec<(usize,usize)>])
+    -> usize
+{
+    let mut basin_scores: Vec<usize> = basins.iter().map(|x| {
+            x.len()
+        }).sorted().collect();
+    assert!(basin_scores.len() >= 3);
+    basin_scores.pop().unwrap() * basin_scores.pop().unwrap() * basin_scores.pop().unwrap()
+}
+
+fn calc_risk(map: &Array2<usize>, lowest_points: &[(usize,usize)])
+    -> usize
+{
+    let mut lowest_map:Array2<usize> = Array2::zeros(map.raw_dim());
+    for idx in lowest_points {
+        lowest_map[*idx] = 1;
+    }
+    (map * &lowest_map).sum() as usize + lowest_map.sum() as usize
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use aoclib::answers;
+    use aoclib::fileops;
+
+    fn get_test_input()
+        -> Array2<usize>
+    {
+        Day9.parse(&fileops::read_file("test_input.txt"))
+    }
+
+    #[test]
+    /// test parse input
+    fn test_0x0001()
+    {
+        let input = get_test_input();
+        assert_eq!(input.ndim(), 2);
+        assert_eq!(input.shape(), [5,10]);
+        assert_eq!(input,
+                   arr2(&[
+                        [2,1,9,9,9,4,3,2,1,0],
+                        [3,9,8,7,8,9,4,9,2,1],
+                        [9,8,5,6,7,8,9,8,9,2],
+                        [8,7,6,7,8,9,6,7,8,9],
+                        [9,8,9,9,9,6,5,6,7,8]
+                   ]));
+    }
+
+    #[test]
+    /// test finding the lowest point
+    fn test_0x0002()
+    {
+        let input = get_test_input();
+        let lowest_points = find_lowest_points(&input);
+        assert_eq!(lowest_points.len(), 4);
+        assert_eq!(lowest_points, vec![(0,1), (0,9), (2,2), (4,6)]);
+    }
+
+    #[test]
+    /// test finding the lowest point
+    fn test_0x0003()
+    {
+        let input = get_test_input();
+        let lowest_points = find_lowest_points(&input);
+        let risk_sum = calc_risk(&input, &lowest_points);
+        assert_eq!(risk_sum, 15);
+        assert_eq!(Day9.part1(&input), Answer::from(15));
+    }
+
+    #[test]
+    /// test finding the lowest point
+    fn test_0x0004()
+    {
+        let input = get_test_input();
+        let lowest_points = find_lowest_points(&input);
+        let basins = find_basins(&input, &lowest_points);
+        assert_eq!(basins.len(), 4);
+        assert_eq!(calc_biggest_basin_prod(&basins), 1134);
+        assert_eq!(Day9.part2(&input), Answer::from(1134));
+    }
+
+    #[test]
+    /// test cells on a plateau are in the basin they drain to
+    fn test_0x0005()
+    {
+        let map = arr2(&[
+            [9,9,9,9,9],
+            [9,1,5,5,9],
+            [9,9,9,9,9],
+        ]);
+        let lowest_points = find_lowest_points(&map);
+        assert_eq!(find_basins(&map, &lowest_points), vec![vec![(1,1), (1,2), (1,3)]]);
+    }
+
+    #[test]
+    /// test input.txt and test_input.txt against answers.toml
+    fn test_0x0006()
+    {
+        answers::assert_answers(&Day9, env!("CARGO_MANIFEST_DIR"));
+    }
+}